@@ -0,0 +1,175 @@
+//! Opt-in counting of allocations
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt::{self, Display},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy)]
+struct Counters {
+    allocs: u64,
+    bytes: u64,
+    current: i64,
+    peak: i64,
+}
+
+thread_local! {
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters { allocs: 0, bytes: 0, current: 0, peak: 0 })
+    };
+}
+
+/// A global allocator that counts allocations per thread
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: aoc::CountingAlloc = aoc::CountingAlloc::new();
+/// ```
+///
+/// With it installed, the runner reports the [`Usage`] of parsing
+/// and every part next to their times.
+#[derive(Default)]
+pub struct CountingAlloc<A = System>(pub A);
+
+impl CountingAlloc {
+    pub const fn new() -> Self {
+        Self(System)
+    }
+}
+
+/// Record an allocation of `size` bytes, or a change of the live bytes
+fn count(size: Option<usize>, change: i64) {
+    // only the first call writes, to keep the flag's cache line shared
+    if !INSTALLED.load(Ordering::Relaxed) {
+        INSTALLED.store(true, Ordering::Relaxed);
+    }
+    COUNTERS
+        .try_with(|counters| {
+            let mut c = counters.get();
+            if let Some(size) = size {
+                c.allocs += 1;
+                c.bytes += size as u64;
+            }
+            c.current += change;
+            c.peak = c.peak.max(c.current);
+            counters.set(c);
+        })
+        .ok();
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAlloc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.0.alloc(layout) };
+        if !ptr.is_null() {
+            count(Some(layout.size()), layout.size() as i64);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.0.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            count(Some(layout.size()), layout.size() as i64);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { self.0.dealloc(ptr, layout) };
+        count(None, -(layout.size() as i64));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = unsafe { self.0.realloc(ptr, layout, new_size) };
+        if !new.is_null() {
+            count(Some(new_size), new_size as i64 - layout.size() as i64);
+        }
+        new
+    }
+}
+
+/// Allocations of one phase, counted by [`CountingAlloc`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    /// Number of allocations, a reallocation counts as one
+    pub allocs: u64,
+    /// Total bytes allocated
+    pub bytes: u64,
+    /// Most bytes live at once, on top of those live before
+    pub peak: u64,
+}
+
+impl Usage {
+    /// Run `f` and count what it allocates on this thread
+    ///
+    /// `None` if no [`CountingAlloc`] is installed. Does not nest.
+    pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Usage>) {
+        let before = COUNTERS.with(|counters| {
+            let mut c = counters.get();
+            c.peak = c.current;
+            counters.set(c);
+            c
+        });
+        let res = f();
+        let after = COUNTERS.with(Cell::get);
+        let usage = INSTALLED.load(Ordering::Relaxed).then(|| Usage {
+            allocs: after.allocs - before.allocs,
+            bytes: after.bytes - before.bytes,
+            peak: (after.peak - before.current) as u64,
+        });
+        (res, usage)
+    }
+}
+
+impl Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} peak, {} allocs, {} total",
+            bytes(self.peak),
+            self.allocs,
+            bytes(self.bytes)
+        )
+    }
+}
+
+/// `n` bytes in B, KiB, MiB or GiB
+fn bytes(n: u64) -> String {
+    let mut size = n as f64;
+    for unit in ["B", "KiB", "MiB"] {
+        if size < 1024.0 {
+            return match unit {
+                "B" => format!("{n} B"),
+                _ => format!("{size:.2} {unit}"),
+            };
+        }
+        size /= 1024.0;
+    }
+    format!("{size:.2} GiB")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOC: CountingAlloc = CountingAlloc::new();
+
+    #[test]
+    fn usage() {
+        let (_, usage) = Usage::measure(|| {
+            let mut v: Vec<u8> = Vec::with_capacity(1000);
+            v.extend([1; 1000]);
+            drop(v);
+            vec![0u8; 100]
+        });
+        let usage = usage.unwrap();
+        assert_eq!((usage.allocs, usage.bytes, usage.peak), (2, 1100, 1000));
+        assert_eq!(usage.to_string(), "1000 B peak, 2 allocs, 1.07 KiB total");
+        assert_eq!(bytes(3 << 20), "3.00 MiB");
+    }
+}
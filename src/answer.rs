@@ -0,0 +1,88 @@
+//! Conversion of solution results, dispatching on whether they are a [`Result`]
+//!
+//! Used through [`fallible!`](crate::fallible!), which picks the first impl
+//! that applies: a `Result` with a [`Display`] error, a `Result` with a
+//! [`Debug`] error, or any other value.
+
+use std::{
+    cell::Cell,
+    fmt::{Debug, Display},
+};
+
+#[doc(hidden)]
+pub struct Wrap<T>(pub Cell<Option<T>>);
+
+impl<T> Wrap<T> {
+    pub fn new(val: T) -> Self {
+        Self(Cell::new(Some(val)))
+    }
+
+    fn take(&self) -> T {
+        self.0.take().unwrap()
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaValue<T> {
+    fn to_result(&self) -> Result<T, String>;
+}
+
+impl<T> ViaValue<T> for Wrap<T> {
+    fn to_result(&self) -> Result<T, String> {
+        Ok(self.take())
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaDebugErr<T> {
+    fn to_result(&self) -> Result<T, String>;
+}
+
+impl<T, E: Debug> ViaDebugErr<T> for &Wrap<Result<T, E>> {
+    fn to_result(&self) -> Result<T, String> {
+        self.take().map_err(|e| format!("{e:?}"))
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaDisplayErr<T> {
+    fn to_result(&self) -> Result<T, String>;
+}
+
+impl<T, E: Display> ViaDisplayErr<T> for &&Wrap<Result<T, E>> {
+    fn to_result(&self) -> Result<T, String> {
+        self.take().map_err(|e| e.to_string())
+    }
+}
+
+/// Turn a value or a `Result` into `Result<_, String>`
+///
+/// Errors are formatted with [`Display`] if possible, [`Debug`] otherwise.
+#[macro_export]
+macro_rules! fallible {
+    ($e:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::answer::{ViaDebugErr as _, ViaDisplayErr as _, ViaValue as _};
+        (&&&$crate::answer::Wrap::new($e)).to_result()
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    #[derive(Debug)]
+    struct OnlyDebug;
+
+    #[test]
+    fn dispatch() {
+        assert_eq!(crate::fallible!(5), Ok(5));
+        assert_eq!(crate::fallible!(Ok::<_, String>(5)), Ok(5));
+        assert_eq!(
+            crate::fallible!(Err::<u8, _>("bad")),
+            Err("bad".to_string())
+        );
+        assert_eq!(
+            crate::fallible!(Err::<u8, _>(OnlyDebug)),
+            Err("OnlyDebug".to_string())
+        );
+    }
+}
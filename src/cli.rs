@@ -0,0 +1,529 @@
+use std::{
+    fmt::{self, Display},
+    ops::RangeInclusive,
+    path::Path,
+};
+
+use crate::{Config, crate_year, extract, new_day};
+
+pub const USAGE: &str = "\
+usage: <day> [input] [options]
+       <from>..<to> | all [options]
+       extract <page.html> [block] [-o <path>]
+       new <day> [type] [options]
+
+  input               `t` / `test` for the example, `t2` / `test2` for
+                      example 2, a day number, a path, or `-` for stdin
+  -p, --part <1|2|both>
+                      which part to run (default: 1)
+  -i, --input <path>  read the input from <path>, `-` is stdin
+  -t, --test [n]      run against the example input, or example <n>
+  -d, --day <n>       run against the real input of day <n>
+  -y, --year <n>      event year (default: `year` in .aoc, the year in the
+                      crate name or directory, or the latest event)
+  -b, --bench <n>     time every phase over <n> runs
+      --timeout <secs>
+                      stop waiting for a part after <secs> seconds,
+                      0 waits forever (default: 30)
+  -j, --jobs <n>      run up to <n> days in parallel
+      --format <text|json>
+                      print results as text or as one JSON object per
+                      part, other output then goes to stderr
+      --no-check      skip checking the declared examples
+      --raw           pass the input on as it is, without normalizing
+                      line endings and trailing blank lines
+  -w, --watch         run again whenever the input or the source changes
+  -x, --cross-check   run every implementation of the parts on the examples
+                      and the input, and compare their answers and times
+      --submit        submit the answers of the real input
+      --accept        remember the answers of the real input as correct
+  -P, --param <name=value>
+                      set a puzzle parameter
+  -v, -vv             print more, shows `debug!` (`trace!` with -vv) output
+  -h, --help          print this message
+
+extract lists the example code blocks of a saved puzzle page, or writes
+the chosen block to <path> (default: inp/test.txt, if that is empty or
+missing; pass -o inp/test-<day>.txt to keep one example per day)
+new creates src/days/dayNN.rs parsing the input as [type], registers it
+in src/main.rs and creates empty input files
+<from>..<to> and all run the real inputs of several days and compare them
+with the accepted answers
+missing real inputs are downloaded, and --submit posts answers, over plain
+http only: set `base_url` in .aoc to an http endpoint, such as a local
+proxy for https://adventofcode.com, and `session` to the session cookie
+
+exit codes: 0 all answers are right or new, 1 a part failed to give an
+answer, 2 bad arguments, 3 an answer differs from the expected or accepted one";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Part {
+    #[default]
+    One,
+    Two,
+    Both,
+}
+
+impl Part {
+    pub fn runs(self, nr: u8) -> bool {
+        matches!(
+            (self, nr),
+            (Part::One, 1) | (Part::Two, 2) | (Part::Both, _)
+        )
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "part 1"),
+            Part::Two => write!(f, "part 2"),
+            Part::Both => write!(f, "both parts"),
+        }
+    }
+}
+
+/// Where the puzzle input is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// an example input, `inp/test.txt` or `inp/test{n}.txt`
+    Example(Option<u8>),
+    /// the real input of the selected day, `inp/{day}.txt`
+    Real,
+    /// an explicit path
+    Path(String),
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source::Example(None)
+    }
+}
+
+/// How results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    /// one JSON object per line and part
+    Json,
+}
+
+/// Subcommands that replace running the solution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// take an example from a saved puzzle page
+    Extract {
+        page: String,
+        block: Option<usize>,
+        out: Option<String>,
+    },
+    /// run every day, or a range of days, against the accepted answers
+    All(Option<RangeInclusive<u8>>),
+    /// start a new day, parsing its input as `data`
+    New { day: u8, data: Option<String> },
+}
+
+impl Command {
+    pub fn run(&self, args: &Args) {
+        let res = match self {
+            Command::Extract { page, block, out } => extract(page, *block, out.as_deref()),
+            // a `Registry` runs these itself
+            Command::All(_) => Err("running several days needs a binary built with `days!`".into()),
+            Command::New { day, data } => new_day(*day, data.as_deref(), args.event_year()),
+        };
+        if let Err(e) = res {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Args {
+    pub command: Option<Command>,
+    pub part: Part,
+    pub source: Source,
+    pub day: Option<u8>,
+    pub year: Option<u16>,
+    pub bench: Option<usize>,
+    pub jobs: Option<usize>,
+    pub timeout: Option<u64>,
+    pub format: Format,
+    pub no_check: bool,
+    pub raw: bool,
+    pub watch: bool,
+    pub cross_check: bool,
+    pub submit: bool,
+    pub accept: bool,
+    pub verbose: u8,
+    pub params: Vec<(String, String)>,
+    pub help: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    UnknownFlag(String),
+    MissingValue(String),
+    InvalidValue(String, String),
+    Unexpected(String),
+    NoDay,
+}
+
+impl Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgError::UnknownFlag(flag) => write!(f, "unknown option `{flag}`"),
+            ArgError::MissingValue(flag) => write!(f, "option `{flag}` needs a value"),
+            ArgError::InvalidValue(flag, val) => {
+                write!(f, "invalid value `{val}` for option `{flag}`")
+            }
+            ArgError::Unexpected(arg) => write!(f, "unexpected argument `{arg}`"),
+            ArgError::NoDay => write!(f, "no day given for the real input, use `--day <n>`"),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+impl Args {
+    /// Parse the process arguments
+    ///
+    /// Prints the usage and exits on `--help` or any error.
+    pub fn parse() -> Self {
+        match Self::parse_from(std::env::args().skip(1)) {
+            Ok(args) if args.help => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            Ok(args) => args,
+            Err(e) => {
+                eprintln!("error: {e}\n\n{USAGE}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    /// Parse arguments, not including the program name
+    pub fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Self, ArgError> {
+        let mut res = Args::default();
+        let mut positional = None;
+        let mut args = args.into_iter().peekable();
+        // subcommands take their own positional arguments and the common flags
+        let sub = args.next_if(|a| a == "extract" || a == "new");
+        let (mut subargs, mut out) = (Vec::new(), None);
+        if args.next_if(|a| a == "all").is_some() {
+            let mut res = Self::parse_from(args.collect::<Vec<_>>())?;
+            res.command = Some(Command::All(None));
+            return Ok(res);
+        }
+        let mut explicit = false;
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, val)) if arg.starts_with("--") => (flag.to_string(), Some(val)),
+                _ => (arg.clone(), None),
+            };
+            let value = |args: &mut std::iter::Peekable<_>| match inline {
+                Some(val) => Ok(val.to_string()),
+                None => args.next().ok_or(ArgError::MissingValue(flag.clone())),
+            };
+            match flag.as_str() {
+                "-h" | "--help" => res.help = true,
+                "-p" | "--part" => {
+                    let val = value(&mut args)?;
+                    res.part = match val.as_str() {
+                        "1" => Part::One,
+                        "2" => Part::Two,
+                        "both" | "b" => Part::Both,
+                        _ => return Err(ArgError::InvalidValue(flag, val)),
+                    };
+                }
+                "-i" | "--input" => {
+                    res.source = Source::Path(value(&mut args)?);
+                    explicit = true;
+                }
+                "-t" | "--test" => {
+                    let nr =
+                        match inline {
+                            Some(val) => Some(val.parse().map_err(|_| {
+                                ArgError::InvalidValue(flag.clone(), val.to_string())
+                            })?),
+                            None => args
+                                .next_if(|a| a.parse::<u8>().is_ok())
+                                .map(|a| a.parse().unwrap()),
+                        };
+                    res.source = Source::Example(nr);
+                    explicit = true;
+                }
+                "-d" | "--day" => {
+                    let val = value(&mut args)?;
+                    res.day = Some(val.parse().map_err(|_| ArgError::InvalidValue(flag, val))?);
+                }
+                "-y" | "--year" => {
+                    let val = value(&mut args)?;
+                    res.year = Some(val.parse().map_err(|_| ArgError::InvalidValue(flag, val))?);
+                }
+                "--format" => {
+                    let val = value(&mut args)?;
+                    res.format = match val.as_str() {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        _ => return Err(ArgError::InvalidValue(flag, val)),
+                    };
+                }
+                "--no-check" => res.no_check = true,
+                "--raw" => res.raw = true,
+                "-w" | "--watch" => res.watch = true,
+                "-x" | "--cross-check" => res.cross_check = true,
+                "--submit" => res.submit = true,
+                "--accept" => res.accept = true,
+                "-v" | "-vv" | "-vvv" => res.verbose += flag.len() as u8 - 1,
+                "-P" | "--param" => {
+                    let val = value(&mut args)?;
+                    let Some((name, param)) = val.split_once('=') else {
+                        return Err(ArgError::InvalidValue(flag, val));
+                    };
+                    res.params.push((name.to_string(), param.to_string()));
+                }
+                "-b" | "--bench" => {
+                    let val = value(&mut args)?;
+                    res.bench = Some(val.parse().map_err(|_| ArgError::InvalidValue(flag, val))?);
+                }
+                "--timeout" => {
+                    let val = value(&mut args)?;
+                    res.timeout = Some(val.parse().map_err(|_| ArgError::InvalidValue(flag, val))?);
+                }
+                "-j" | "--jobs" => {
+                    let val = value(&mut args)?;
+                    res.jobs = Some(val.parse().map_err(|_| ArgError::InvalidValue(flag, val))?);
+                }
+                "-o" | "--out" if sub.as_deref() == Some("extract") => {
+                    out = Some(value(&mut args)?)
+                }
+                _ if flag.starts_with('-') && flag != "-" => {
+                    return Err(ArgError::UnknownFlag(flag));
+                }
+                _ if sub.is_some() => subargs.push(arg),
+                _ if positional.is_none() => positional = Some(arg),
+                // `<day> <input>`
+                _ => match positional.as_deref().map(str::parse::<u8>) {
+                    Some(Ok(day)) if res.day.is_none() && arg.parse::<u8>().is_err() => {
+                        res.day = Some(day);
+                        positional = Some(arg);
+                        explicit = true;
+                    }
+                    _ => return Err(ArgError::Unexpected(arg)),
+                },
+            }
+        }
+        if let Some(sub) = sub {
+            res.command = Some(Self::subcommand(&sub, subargs, out)?);
+            return Ok(res);
+        }
+        if let Some(pos) = positional {
+            let lower = pos.to_lowercase();
+            let example = lower.strip_prefix("test").or(lower.strip_prefix('t'));
+            let range = pos
+                .split_once("..")
+                .map(|(from, to)| (from.parse(), to.trim_start_matches('=').parse()));
+            if let Some(nr) = example.filter(|nr| nr.chars().all(|c| c.is_ascii_digit())) {
+                res.source = Source::Example(nr.parse().ok());
+            } else if let Ok(day) = pos.parse() {
+                res.day = Some(day);
+            } else if let Some((Ok(from), Ok(to))) = range {
+                res.command = Some(Command::All(Some(from..=to)));
+            } else {
+                res.source = Source::Path(pos);
+            }
+        }
+        // a day alone selects its real input, `-t` and `-i` still win
+        if res.day.is_some() && !explicit {
+            res.source = Source::Real;
+        }
+        Ok(res)
+    }
+
+    /// `extract` or `new` from their positional arguments
+    fn subcommand(sub: &str, args: Vec<String>, out: Option<String>) -> Result<Command, ArgError> {
+        let mut args = args.into_iter();
+        let first = args.next().ok_or(ArgError::MissingValue(sub.into()))?;
+        let second = args.next();
+        if let Some(arg) = args.next() {
+            return Err(ArgError::Unexpected(arg));
+        }
+        Ok(match sub {
+            "extract" => Command::Extract {
+                page: first,
+                block: match second {
+                    Some(arg) => Some(
+                        arg.parse()
+                            .map_err(|_| ArgError::InvalidValue("block".into(), arg))?,
+                    ),
+                    None => None,
+                },
+                out,
+            },
+            _ => Command::New {
+                day: first
+                    .parse()
+                    .map_err(|_| ArgError::InvalidValue(sub.into(), first))?,
+                data: second,
+            },
+        })
+    }
+
+    /// The event year: `--year`, the config, or [`crate_year`]
+    pub fn event_year(&self) -> Option<u16> {
+        self.year
+            .or_else(|| Config::load().year)
+            .or_else(crate_year)
+    }
+
+    /// Print a message for humans, to stderr if stdout is JSON
+    pub fn note(&self, msg: impl Display) {
+        match self.format {
+            Format::Text => println!("{msg}"),
+            Format::Json => eprintln!("{msg}"),
+        }
+    }
+
+    pub fn testing(&self) -> bool {
+        match &self.source {
+            Source::Example(_) => true,
+            Source::Real => false,
+            Source::Path(path) => path.contains("test"),
+        }
+    }
+
+    pub fn input_path(&self) -> Result<String, ArgError> {
+        Ok(match &self.source {
+            Source::Example(None) => "inp/test.txt".to_string(),
+            Source::Example(Some(nr)) => format!("inp/test{nr}.txt"),
+            Source::Real => {
+                let day = self.day.ok_or(ArgError::NoDay)?;
+                let legacy = format!("inp/{day}.txt");
+                match self.event_year() {
+                    // inputs from before the year directories still count
+                    Some(year) if !Path::new(&legacy).exists() => format!("inp/{year}/{day}.txt"),
+                    _ => legacy,
+                }
+            }
+            Source::Path(path) => path.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Args, ArgError> {
+        Args::parse_from(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn positional() {
+        assert_eq!(parse("").unwrap().input_path().unwrap(), "inp/test.txt");
+        assert_eq!(parse("T").unwrap().source, Source::Example(None));
+        assert_eq!(parse("t2").unwrap().source, Source::Example(Some(2)));
+        assert_eq!(
+            parse("test3").unwrap().input_path().unwrap(),
+            "inp/test3.txt"
+        );
+        assert_eq!(
+            parse("tmp.txt").unwrap().source,
+            Source::Path("tmp.txt".into())
+        );
+        let args = parse("3").unwrap();
+        assert_eq!((args.day, args.source), (Some(3), Source::Real));
+        assert_eq!(
+            parse("foo.txt").unwrap().source,
+            Source::Path("foo.txt".into())
+        );
+        assert_eq!(parse("-").unwrap().input_path().unwrap(), "-");
+        assert_eq!(parse("3 4"), Err(ArgError::Unexpected("4".into())));
+        let args = parse("3 t2").unwrap();
+        assert_eq!((args.day, args.source), (Some(3), Source::Example(Some(2))));
+        let args = parse("3 -").unwrap();
+        assert_eq!(
+            (args.day, args.input_path().unwrap()),
+            (Some(3), "-".into())
+        );
+        assert_eq!(parse("3 t x"), Err(ArgError::Unexpected("x".into())));
+        assert_eq!(parse("t 3"), Err(ArgError::Unexpected("3".into())));
+    }
+
+    #[test]
+    fn flags() {
+        let args = parse("--part both -d 12 --year=2024").unwrap();
+        assert_eq!(args.part, Part::Both);
+        assert_eq!(args.input_path().unwrap(), "inp/2024/12.txt");
+        assert_eq!(args.year, Some(2024));
+        assert_eq!(parse("--bench 10").unwrap().bench, Some(10));
+        assert_eq!(parse("--format=json").unwrap().format, Format::Json);
+        assert_eq!(parse("-v -vv").unwrap().verbose, 3);
+        assert!(parse("3 -x").unwrap().cross_check);
+        let params = parse("-P steps=64 --param=size=7").unwrap().params;
+        assert_eq!(params[1], ("size".to_string(), "7".to_string()));
+        assert!(!args.testing());
+        assert_eq!(
+            parse("--part 3"),
+            Err(ArgError::InvalidValue("--part".into(), "3".into()))
+        );
+        assert_eq!(
+            parse("-t 2").unwrap().input_path().unwrap(),
+            "inp/test2.txt"
+        );
+        assert_eq!(
+            parse("-t -p 2").unwrap().input_path().unwrap(),
+            "inp/test.txt"
+        );
+        assert_eq!(parse("--day"), Err(ArgError::MissingValue("--day".into())));
+        assert_eq!(
+            parse("extract day3.html 1 -o inp/test2.txt")
+                .unwrap()
+                .command,
+            Some(Command::Extract {
+                page: "day3.html".into(),
+                block: Some(1),
+                out: Some("inp/test2.txt".into()),
+            })
+        );
+        let args = parse("new 5 Map2D<char> -y 2025").unwrap();
+        assert_eq!(
+            (args.command, args.year),
+            (
+                Some(Command::New {
+                    day: 5,
+                    data: Some("Map2D<char>".into())
+                }),
+                Some(2025)
+            )
+        );
+        assert_eq!(parse("new 5 a b"), Err(ArgError::Unexpected("b".into())));
+        assert_eq!(parse("3 -o x"), Err(ArgError::UnknownFlag("-o".into())));
+        let args = parse("all -y 2024").unwrap();
+        assert_eq!(
+            (args.command, args.year),
+            (Some(Command::All(None)), Some(2024))
+        );
+        let args = parse("1..12 -j 4").unwrap();
+        assert_eq!(
+            (args.command, args.jobs),
+            (Some(Command::All(Some(1..=12))), Some(4))
+        );
+        assert_eq!(
+            parse("new 5 Map2D<char>").unwrap().command,
+            Some(Command::New {
+                day: 5,
+                data: Some("Map2D<char>".into())
+            })
+        );
+        let args = parse("3 -t").unwrap();
+        assert_eq!((args.day, args.source), (Some(3), Source::Example(None)));
+        assert_eq!(
+            parse("--frobnicate"),
+            Err(ArgError::UnknownFlag("--frobnicate".into()))
+        );
+    }
+}
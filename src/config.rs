@@ -0,0 +1,138 @@
+use std::{
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Settings from the `.aoc` file and the environment
+///
+/// The file holds `key = value` lines, `#` starts a comment.
+/// `AOC_SESSION`, `AOC_BASE_URL` and `AOC_YEAR` override it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Config {
+    /// value of the `session` cookie
+    pub session: Option<String>,
+    /// the site to download inputs from, see [`USAGE`](crate::USAGE)
+    pub base_url: Option<String>,
+    pub year: Option<u16>,
+}
+
+impl Config {
+    pub const FILE: &str = ".aoc";
+
+    pub fn load() -> Self {
+        let mut cfg = std::fs::read_to_string(Self::FILE)
+            .map(|text| Self::parse(&text))
+            .unwrap_or_default();
+        for (key, var) in [
+            ("session", "AOC_SESSION"),
+            ("base_url", "AOC_BASE_URL"),
+            ("year", "AOC_YEAR"),
+        ] {
+            if let Ok(val) = std::env::var(var) {
+                cfg.set(key, &val);
+            }
+        }
+        cfg
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut cfg = Self::default();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap();
+            if let Some((key, val)) = line.split_once('=') {
+                cfg.set(key.trim(), val.trim());
+            }
+        }
+        cfg
+    }
+
+    fn set(&mut self, key: &str, val: &str) {
+        match key {
+            "session" => self.session = Some(val.to_string()),
+            "base_url" => self.base_url = Some(val.trim_end_matches('/').to_string()),
+            "year" => self.year = val.parse().ok(),
+            _ => {}
+        }
+    }
+}
+
+static CRATE: OnceLock<[&str; 2]> = OnceLock::new();
+
+/// Remember the package name and directory of the solution crate,
+/// done by the `main` of [`aoc!`](crate::aoc!) and [`days!`](crate::days!)
+pub fn set_crate(name: &'static str, dir: &'static str) {
+    CRATE.get_or_init(|| [name, dir]);
+}
+
+/// The year in the crate name, such as `aoc2025`, or in its directory,
+/// such as `advent/2025`, else the latest event
+pub fn crate_year() -> Option<u16> {
+    let exe = std::env::current_exe().ok();
+    let exe = exe.as_ref().and_then(|exe| exe.file_stem()?.to_str());
+    let names = CRATE
+        .get()
+        .into_iter()
+        .flatten()
+        .map(|name| name.to_string());
+    let vars = ["CARGO_PKG_NAME", "CARGO_MANIFEST_DIR"].map(std::env::var);
+    names
+        .chain(vars.into_iter().flatten())
+        .chain(exe.map(String::from))
+        .find_map(|name| year_in(&name))
+        .or(Some(latest_event(SystemTime::now())))
+}
+
+/// The year of the last event that started by `now`, events start in December
+fn latest_event(now: SystemTime) -> u16 {
+    let days = now
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86400) as i64;
+    // civil date from days since 1970-01-01, after Howard Hinnant
+    let z = days + 719_468;
+    let (era, doe) = (z.div_euclid(146_097), z.rem_euclid(146_097));
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    (year - (month < 12) as i64) as u16
+}
+
+/// The last four-digit number in `name` that can be an event year
+fn year_in(name: &str) -> Option<u16> {
+    name.split(|c: char| !c.is_ascii_digit())
+        .filter(|digits| digits.len() == 4)
+        .flat_map(str::parse)
+        .rfind(|year| (2015..2100).contains(year))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn years() {
+        assert_eq!(year_in("aoc2025"), Some(2025));
+        assert_eq!(year_in("advent-2019-rs"), Some(2019));
+        assert_eq!(year_in("aoc"), None);
+        assert_eq!(year_in("day12345"), None);
+        assert_eq!(year_in("/home/me/advent/2019/aoc"), Some(2019));
+        let day = |days: u64| UNIX_EPOCH + std::time::Duration::from_secs(days * 86400);
+        // 2025-10-18, 2025-12-01 and 2026-01-01
+        assert_eq!(latest_event(day(20379)), 2024);
+        assert_eq!(latest_event(day(20423)), 2025);
+        assert_eq!(latest_event(day(20454)), 2025);
+        assert!(crate_year().is_some_and(|year| year >= 2025));
+    }
+
+    #[test]
+    fn parse() {
+        let cfg = Config::parse(
+            "# settings\nsession = abc123\nbase_url=http://localhost:8080/ # local\n",
+        );
+        assert_eq!(cfg.session.as_deref(), Some("abc123"));
+        assert_eq!(cfg.base_url.as_deref(), Some("http://localhost:8080"));
+        assert_eq!(Config::parse("").base_url, None);
+        assert_eq!(cfg.year, None);
+    }
+}
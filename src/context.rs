@@ -0,0 +1,115 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, str::FromStr, time::Duration};
+
+use crate::Args;
+
+/// What a solution is currently run on
+///
+/// The runner keeps the context of the running solution in a thread-local,
+/// see [`with`](RunContext::with). `input`, `part1` and `part2` can also
+/// take it as an additional `&RunContext` argument.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunContext {
+    /// running on an example
+    pub testing: bool,
+    /// the day given on the command line
+    pub day: Option<u8>,
+    /// the event year, see [`Args::event_year`]
+    pub year: Option<u16>,
+    /// path of the input
+    pub input: String,
+    /// the running part, `None` while parsing
+    pub part: Option<u8>,
+    /// number of `-v` flags
+    pub verbosity: u8,
+    /// parameters, declared or given by `--param name=value`
+    pub params: HashMap<String, String>,
+    /// how long parsing and each part may run, see [`isolated`](crate::isolated)
+    pub timeout: Option<Duration>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<RunContext> = RefCell::default();
+}
+
+impl RunContext {
+    pub fn new(args: &Args, input: &str, testing: bool) -> Self {
+        Self {
+            testing,
+            day: args.day,
+            year: args.event_year(),
+            input: input.to_string(),
+            part: None,
+            verbosity: args.verbose,
+            params: args.params.iter().cloned().collect(),
+            timeout: match args.timeout {
+                Some(0) => None,
+                secs => Some(Duration::from_secs(secs.unwrap_or(30))),
+            },
+        }
+    }
+
+    /// Access the context of the running solution
+    pub fn with<T>(f: impl FnOnce(&RunContext) -> T) -> T {
+        CURRENT.with_borrow(f)
+    }
+
+    /// Make this the context of the running solution
+    pub fn enter(&self) {
+        CURRENT.set(self.clone());
+    }
+
+    /// Whether [`debug!`](crate::debug!) (level 1) or [`trace!`](crate::trace!)
+    /// (level 2) print, running an example counts as one `-v`
+    pub fn shows(&self, level: u8) -> bool {
+        self.verbosity + self.testing as u8 >= level
+    }
+
+    /// Parse the value of a `--param`
+    pub fn param<T>(&self, name: &str) -> Option<T>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Debug,
+    {
+        self.params.get(name).map(|val| val.parse().unwrap())
+    }
+}
+
+#[doc(hidden)]
+pub struct NoCtx;
+#[doc(hidden)]
+pub struct WithCtx;
+
+/// Functions taking an optional `&RunContext` as their last argument
+///
+/// Lets [`aoc!`](crate::aoc!) call `input` and the parts
+/// regardless of whether they want the context.
+pub trait CtxFn<A, R, M> {
+    fn call_ctx(&self, arg: A, ctx: &RunContext) -> R;
+}
+
+impl<F: Fn(A) -> R, A, R> CtxFn<A, R, NoCtx> for F {
+    fn call_ctx(&self, arg: A, _: &RunContext) -> R {
+        self(arg)
+    }
+}
+
+impl<F: Fn(A, &RunContext) -> R, A, R> CtxFn<A, R, WithCtx> for F {
+    fn call_ctx(&self, arg: A, ctx: &RunContext) -> R {
+        self(arg, ctx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn levels() {
+        let mut ctx = RunContext::default();
+        assert!(!ctx.shows(1));
+        ctx.testing = true;
+        assert!(ctx.shows(1) && !ctx.shows(2));
+        ctx.verbosity = 1;
+        assert!(ctx.shows(2));
+    }
+}
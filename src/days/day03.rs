@@ -0,0 +1,54 @@
+aoc::aoc!(part1, part2 | part2_stack as ref Map2D<u8>; examples {
+    "test.txt" => (357, 3121910778619),
+});
+
+fn input(inp: String) -> Data {
+    inp.map().to(ctd)
+}
+
+fn part1(inp: &Data) -> u64 {
+    let mut sum = 0;
+    for line in inp.iter() {
+        let first = *line[..line.len() - 1].iter().max().unwrap();
+        let first_pos = line.iter().position(|&x| x == first).unwrap();
+        let second = *line[first_pos + 1..].iter().max().unwrap();
+        sum += (first * 10 + second) as u64;
+    }
+    sum
+}
+
+fn part2(inp: &Data) -> u64 {
+    let mut sum = 0;
+    for line in inp.iter() {
+        let mut jolt = 0;
+        let mut curr_pos = 0;
+        for i in (0..12).rev() {
+            let digit = line[curr_pos..line.len() - i].iter().max().unwrap();
+            curr_pos += line[curr_pos..line.len() - i]
+                .iter()
+                .position(|x| x == digit)
+                .unwrap()
+                + 1;
+            jolt = jolt * 10 + (*digit as u64);
+        }
+        sum += jolt;
+    }
+    sum
+}
+
+fn part2_stack(inp: &Data) -> u64 {
+    let mut sum = 0;
+    for line in inp.iter() {
+        let mut skip = line.len() - 12;
+        let mut digits = Vec::with_capacity(line.len());
+        for &digit in line.iter() {
+            while skip > 0 && digits.last().is_some_and(|&last| last < digit) {
+                digits.pop();
+                skip -= 1;
+            }
+            digits.push(digit);
+        }
+        sum += digits[..12].iter().fold(0, |jolt, &d| jolt * 10 + d as u64);
+    }
+    sum
+}
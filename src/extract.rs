@@ -0,0 +1,162 @@
+//! Examples from saved puzzle pages
+
+use std::{error::Error, path::Path};
+
+/// Contents of the `<pre><code>` blocks
+pub fn code_blocks(html: &str) -> Vec<String> {
+    let mut res = Vec::new();
+    let mut rest = html;
+    while let Some(start) = find_tag(rest, "pre") {
+        rest = &rest[start..];
+        let Some(code) = find_tag(rest, "code") else {
+            break;
+        };
+        let end = find_ci(rest, "</pre").unwrap_or(rest.len());
+        if code < end {
+            let inner = &rest[code..end];
+            let inner = &inner[inner.find('>').map_or(0, |i| i + 1)..];
+            let inner = &inner[..find_ci(inner, "</code").unwrap_or(inner.len())];
+            res.push(text(inner));
+        }
+        rest = &rest[end..];
+    }
+    res
+}
+
+/// The last `<code><em>` of every part, the likely answers
+pub fn answer_guesses(html: &str) -> Vec<String> {
+    let mut parts: Vec<_> = html.split("<article").skip(1).collect();
+    if parts.is_empty() {
+        parts.push(html);
+    }
+    parts
+        .into_iter()
+        .flat_map(|part| {
+            part.match_indices("<code><em>")
+                .filter_map(|(i, m)| {
+                    let inner = &part[i + m.len()..];
+                    Some(text(&inner[..inner.find("</em>")?]))
+                })
+                .last()
+        })
+        .collect()
+}
+
+/// Write code block `block` of `page` to `out`, or list the blocks if none is chosen
+///
+/// Without `out`, writes `inp/test.txt` unless that already holds an example.
+pub fn extract(page: &str, block: Option<usize>, out: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let html = std::fs::read_to_string(page).map_err(|e| format!("cannot read `{page}`: {e}"))?;
+    let blocks = code_blocks(&html);
+    let guesses = answer_guesses(&html);
+    let Some(nr) = block else {
+        for (nr, block) in blocks.iter().enumerate() {
+            let lines = block.lines().count();
+            println!("[{nr}] {lines} line(s)");
+            for line in block.lines().take(3) {
+                println!("    {line}");
+            }
+            if lines > 3 {
+                println!("    ...");
+            }
+        }
+        if blocks.is_empty() {
+            println!("no code blocks found");
+        }
+        return Ok(());
+    };
+    let block = blocks
+        .get(nr)
+        .ok_or_else(|| format!("no code block {nr}, the page has {}", blocks.len()))?;
+    let path = out.unwrap_or("inp/test.txt");
+    if out.is_none() && std::fs::metadata(path).is_ok_and(|m| m.len() > 0) {
+        return Err(format!("`{path}` exists, choose where to write with `-o`").into());
+    }
+    if let Some(dir) = Path::new(path).parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, block)?;
+    println!("wrote block {nr} to {path}");
+    if !guesses.is_empty() {
+        let file = path.strip_prefix("inp/").unwrap_or(path);
+        println!("examples {{ \"{file}\" => ({}) }}", guesses.join(", "));
+    }
+    Ok(())
+}
+
+/// Position right after `<{name}`, if followed by `>` or an attribute
+fn find_tag(html: &str, name: &str) -> Option<usize> {
+    let open = format!("<{name}");
+    let mut from = 0;
+    while let Some(i) = find_ci(&html[from..], &open) {
+        let end = from + i + open.len();
+        if html[end..].starts_with(|c: char| c == '>' || c.is_whitespace()) {
+            return Some(end);
+        }
+        from = end;
+    }
+    None
+}
+
+fn find_ci(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// Strip tags and decode entities
+fn text(html: &str) -> String {
+    let mut res = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => res.push(c),
+            _ => {}
+        }
+    }
+    res.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn page() {
+        let html = "<article><p>For example:</p>\n<PRE><code>987654321111111\n<em>81</em>1111111111111&lt;\n</code></PRE>\
+            <p>The total is <code><em>3</em></code> or <code><em>357</em></code>.</p></article>\
+            <p>Your answer:</p><pre class=\"x\"><code>x &amp; y</code></pre>\
+            <article><p>Now it is <code><em>3121910778619</em></code>.</p></article>";
+        assert_eq!(
+            code_blocks(html),
+            ["987654321111111\n811111111111111<\n", "x & y"]
+        );
+        assert_eq!(answer_guesses(html), ["357", "3121910778619"]);
+    }
+
+    #[test]
+    fn no_overwrite() {
+        let dir = std::env::temp_dir().join(format!("aoc-extract-{}", std::process::id()));
+        let (page, out) = (dir.join("page.html"), dir.join("test-3.txt"));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&page, "<pre><code>1 2\n</code></pre>").unwrap();
+        let page = page.to_str().unwrap();
+        let before = std::fs::read_to_string("inp/test.txt").unwrap();
+        let err = extract(page, Some(0), None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`inp/test.txt` exists, choose where to write with `-o`"
+        );
+        assert_eq!(std::fs::read_to_string("inp/test.txt").unwrap(), before);
+        extract(page, Some(0), out.to_str()).unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "1 2\n");
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    fs, io,
+    path::PathBuf,
+};
+
+use crate::{Config, HttpError, http};
+
+pub(crate) const USER_AGENT: &str = "github.com/ba-lindner/aoc2025 by ba-lindner";
+
+#[derive(Debug)]
+pub enum FetchError {
+    NoSession,
+    NoBaseUrl,
+    NoYear,
+    Http(HttpError),
+    Status(u16, String),
+    Io(io::Error),
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::NoSession => write!(
+                f,
+                "no session token, set `session` in `{}` or `AOC_SESSION`",
+                Config::FILE
+            ),
+            FetchError::NoBaseUrl => write!(
+                f,
+                "no base url, set `base_url` in `{}` or `AOC_BASE_URL`",
+                Config::FILE
+            ),
+            FetchError::NoYear => write!(f, "no year given, use `--year <n>`"),
+            FetchError::Http(e) => write!(f, "request failed: {e}"),
+            FetchError::Status(status, body) => write!(f, "request failed ({status}): {body}"),
+            FetchError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl Error for FetchError {}
+
+impl From<HttpError> for FetchError {
+    fn from(e: HttpError) -> Self {
+        FetchError::Http(e)
+    }
+}
+
+impl From<io::Error> for FetchError {
+    fn from(e: io::Error) -> Self {
+        FetchError::Io(e)
+    }
+}
+
+/// Client for the puzzle site
+///
+/// Downloads inputs into an on-disk cache, see also [`submit`](Fetcher::submit).
+pub struct Fetcher {
+    pub base_url: Option<String>,
+    pub session: Option<String>,
+    pub dir: PathBuf,
+}
+
+impl Fetcher {
+    pub fn new(cfg: &Config) -> Self {
+        Self {
+            base_url: cfg.base_url.clone(),
+            session: cfg.session.clone(),
+            dir: PathBuf::from("inp"),
+        }
+    }
+
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.dir.join(year.to_string()).join(format!("{day}.txt"))
+    }
+
+    /// The input of a day, only downloaded if it is not cached yet
+    ///
+    /// An empty file, as created by `new`, does not count as cached.
+    pub fn input(&self, year: u16, day: u8) -> Result<String, FetchError> {
+        let path = self.path(year, day);
+        if let Ok(inp) = fs::read_to_string(&path)
+            && !inp.is_empty()
+        {
+            return Ok(inp);
+        }
+        let base_url = self.base_url.as_deref().ok_or(FetchError::NoBaseUrl)?;
+        let session = self.session.as_deref().ok_or(FetchError::NoSession)?;
+        let url = format!("{base_url}/{year}/day/{day}/input");
+        let cookie = format!("session={session}");
+        let res = http::get(&url, &[("Cookie", &cookie), ("User-Agent", USER_AGENT)])?;
+        if res.status != 200 {
+            return Err(FetchError::Status(res.status, res.body.trim().to_string()));
+        }
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(&path, &res.body)?;
+        Ok(res.body)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    #[test]
+    fn download_once() {
+        let server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = server.accept().unwrap();
+            let head: Vec<String> = BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|l| !l.is_empty())
+                .collect();
+            let body = "1 2 3\n";
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            head
+        });
+        let dir = std::env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+        let fetcher = Fetcher {
+            base_url: Some(format!("http://{addr}")),
+            session: Some("secret".to_string()),
+            dir: dir.clone(),
+        };
+        assert_eq!(fetcher.input(2025, 3).unwrap(), "1 2 3\n");
+        let head = handle.join().unwrap();
+        assert_eq!(head[0], "GET /2025/day/3/input HTTP/1.1");
+        assert!(head.contains(&"Cookie: session=secret".to_string()));
+        // the server is gone, so this must come from the cache
+        assert_eq!(fetcher.input(2025, 3).unwrap(), "1 2 3\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,153 @@
+//! Minimal HTTP/1.1 client, plain HTTP only
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io::{self, Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+#[derive(Debug)]
+pub enum HttpError {
+    Url(String),
+    Io(io::Error),
+    Response(String),
+}
+
+impl Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::Url(url) if url.starts_with("https://") => {
+                write!(f, "https is not supported: `{url}`")
+            }
+            HttpError::Url(url) => write!(f, "invalid url `{url}`"),
+            HttpError::Io(e) => write!(f, "{e}"),
+            HttpError::Response(msg) => write!(f, "malformed response: {msg}"),
+        }
+    }
+}
+
+impl Error for HttpError {}
+
+impl From<io::Error> for HttpError {
+    fn from(e: io::Error) -> Self {
+        HttpError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Response {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+pub fn get(url: &str, headers: &[(&str, &str)]) -> Result<Response, HttpError> {
+    request("GET", url, headers, "")
+}
+
+/// POST a `application/x-www-form-urlencoded` body
+pub fn post(url: &str, headers: &[(&str, &str)], body: &str) -> Result<Response, HttpError> {
+    let mut headers = headers.to_vec();
+    headers.push(("Content-Type", "application/x-www-form-urlencoded"));
+    request("POST", url, &headers, body)
+}
+
+pub fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> Result<Response, HttpError> {
+    let (host, path) = url
+        .strip_prefix("http://")
+        .map(|rest| rest.split_at(rest.find('/').unwrap_or(rest.len())))
+        .filter(|(host, _)| !host.is_empty())
+        .ok_or_else(|| HttpError::Url(url.to_string()))?;
+    let addr = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{host}:80")
+    };
+    let mut stream = TcpStream::connect(addr)?;
+    stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+    let mut req = format!(
+        "{method} {} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\nContent-Length: {}\r\n",
+        if path.is_empty() { "/" } else { path },
+        body.len()
+    );
+    for (name, val) in headers {
+        req += &format!("{name}: {val}\r\n");
+    }
+    req += "\r\n";
+    req += body;
+    stream.write_all(req.as_bytes())?;
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw)?;
+    parse_response(&String::from_utf8_lossy(&raw))
+}
+
+fn parse_response(raw: &str) -> Result<Response, HttpError> {
+    let malformed = |msg: &str| HttpError::Response(msg.to_string());
+    let (head, body) = raw
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| malformed("no end of headers"))?;
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| malformed("bad status line"))?;
+    let headers = lines
+        .flat_map(|l| l.split_once(':'))
+        .map(|(n, v)| (n.trim().to_string(), v.trim().to_string()))
+        .collect();
+    let mut res = Response {
+        status,
+        headers,
+        body: body.to_string(),
+    };
+    if res
+        .header("Transfer-Encoding")
+        .is_some_and(|te| te.eq_ignore_ascii_case("chunked"))
+    {
+        res.body = dechunk(body).ok_or_else(|| malformed("bad chunk"))?;
+    }
+    Ok(res)
+}
+
+fn dechunk(mut body: &str) -> Option<String> {
+    let mut res = String::new();
+    loop {
+        let (size, rest) = body.split_once("\r\n")?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        if size == 0 {
+            return Some(res);
+        }
+        res += rest.get(..size)?;
+        body = rest.get(size..)?.strip_prefix("\r\n")?;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn chunked() {
+        let raw = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n7\r\n, world\r\n0\r\n\r\n";
+        let res = parse_response(raw).unwrap();
+        assert_eq!(res.status, 200);
+        assert_eq!(res.body, "hello, world");
+    }
+}
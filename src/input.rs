@@ -0,0 +1,156 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io::{self, Read},
+};
+
+use crate::{Args, Config, FetchError, Fetcher, RunContext, Source};
+
+/// Failure to read a puzzle input
+#[derive(Debug)]
+pub struct InputError {
+    pub path: String,
+    pub err: io::Error,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path == "-" {
+            write!(f, "cannot read input from stdin: {}", self.err)
+        } else {
+            write!(f, "cannot read input `{}`: {}", self.path, self.err)
+        }
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.err)
+    }
+}
+
+/// Read an input file, `-` reads stdin
+pub fn read_source(path: &str) -> Result<String, InputError> {
+    let res = if path == "-" {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf).map(|_| buf)
+    } else {
+        std::fs::read_to_string(path)
+    };
+    res.map_err(|err| InputError {
+        path: path.to_string(),
+        err,
+    })
+}
+
+/// Canonical form of an input: no byte order mark, `\n` line endings,
+/// no trailing blank lines and a single final newline
+pub fn normalize(raw: &str) -> String {
+    let text = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+    let mut lines: Vec<_> = text
+        .split('\n')
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .collect();
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    let mut res = lines.join("\n");
+    if !res.is_empty() {
+        res.push('\n');
+    }
+    res
+}
+
+/// [`normalize`] the input, unless `--raw` is given
+pub fn prepare(args: &Args, raw: String) -> String {
+    if args.raw { raw } else { normalize(&raw) }
+}
+
+/// Read the input at `path`, downloading the real input if it is missing or empty
+pub fn load_input(args: &Args, path: &str) -> Result<String, Box<dyn Error>> {
+    if let (Source::Real, Some(day)) = (&args.source, args.day)
+        && !std::fs::metadata(path).is_ok_and(|m| m.len() > 0)
+    {
+        let year = args.event_year().ok_or(FetchError::NoYear)?;
+        let raw = Fetcher::new(&Config::load()).input(year, day)?;
+        return Ok(prepare(args, raw));
+    }
+    Ok(prepare(args, read_source(path)?))
+}
+
+pub fn inp() -> String {
+    read_input(&Args::parse())
+}
+
+/// The input selected by `args`, see [`load_input`]
+pub fn read_input(args: &Args) -> String {
+    let path = args.input_path().unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        std::process::exit(2);
+    });
+    RunContext::new(args, &path, args.testing()).enter();
+    load_input(args, &path).unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn normalized() {
+        assert_eq!(
+            normalize("\u{feff}1 2\r\n\r\n3 4\r\n \r\n\n"),
+            "1 2\n\n3 4\n"
+        );
+        assert_eq!(normalize("#.#\n..#"), "#.#\n..#\n");
+        assert_eq!(normalize("\n\n"), "");
+    }
+
+    #[test]
+    fn missing() {
+        let path = "inp/missing.txt";
+        let err = read_source(path).unwrap_err();
+        assert_eq!(err.err.kind(), io::ErrorKind::NotFound);
+        assert!(
+            err.to_string()
+                .starts_with("cannot read input `inp/missing.txt`: "),
+            "{err}"
+        );
+        let args = Args {
+            source: Source::Path(path.into()),
+            ..Args::default()
+        };
+        let err = load_input(&args, path).unwrap_err();
+        assert!(err.to_string().contains("`inp/missing.txt`"), "{err}");
+    }
+
+    mod in_memory {
+        crate::aoc!(part1, part2 as Vec<u32>);
+
+        fn input(inp: String) -> Result<Vec<u32>, std::num::ParseIntError> {
+            inp.lines().map(str::parse).collect()
+        }
+
+        fn part1(nums: Vec<u32>) -> u32 {
+            nums.iter().sum()
+        }
+
+        fn part2(nums: Vec<u32>) -> usize {
+            nums.len()
+        }
+
+        #[test]
+        fn answers() {
+            let runner = aoc_runner();
+            let [part1, part2] = runner.answers("1\r\n2\r\n\r\n");
+            assert_eq!((part1, part2), (Some(Ok("3".into())), Some(Ok("2".into()))));
+            let [part1, part2] = runner.answers("1\nx\n");
+            let err = part1.unwrap().unwrap_err();
+            assert!(err.contains("invalid digit"), "{err}");
+            assert_eq!(part2, Some(Err(err)));
+        }
+    }
+}
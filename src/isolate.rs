@@ -0,0 +1,113 @@
+//! Running solution code on its own thread
+
+use std::{
+    any::Any,
+    cell::Cell,
+    panic::{self, AssertUnwindSafe},
+    sync::{Once, mpsc},
+    thread,
+};
+
+use crate::RunContext;
+
+const THREAD: &str = "aoc-part";
+/// Room for deep recursion, more than the 8 MiB of the main thread,
+/// as a stack overflow aborts the whole process
+const STACK: usize = 256 << 20;
+
+thread_local! {
+    static PANIC: Cell<Option<String>> = const { Cell::new(None) };
+}
+
+/// Run `f` on its own thread in the context `ctx`
+///
+/// A panic, or running longer than `ctx.timeout`, becomes an error.
+/// A thread that timed out is left running until the process exits.
+pub fn isolated<T: Send + 'static>(
+    ctx: &RunContext,
+    f: impl FnOnce(&RunContext) -> T + Send + 'static,
+) -> Result<T, String> {
+    capture_panics();
+    let (tx, rx) = mpsc::channel();
+    let ctx = ctx.clone();
+    let timeout = ctx.timeout;
+    thread::Builder::new()
+        .name(THREAD.to_string())
+        .stack_size(STACK)
+        .spawn(move || {
+            ctx.enter();
+            let res = panic::catch_unwind(AssertUnwindSafe(|| f(&ctx)))
+                .map_err(|payload| PANIC.take().unwrap_or_else(|| message(&*payload)));
+            tx.send(res).ok();
+        })
+        .map_err(|e| format!("cannot start a thread: {e}"))?;
+    match timeout {
+        Some(timeout) => rx
+            .recv_timeout(timeout)
+            .map_err(|_| format!("timed out after {timeout:?}"))?,
+        None => rx.recv().map_err(|_| "thread vanished".to_string())?,
+    }
+}
+
+/// Keep the panic messages of isolated threads off stderr, with their location
+fn capture_panics() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let prev = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if thread::current().name() != Some(THREAD) {
+                return prev(info);
+            }
+            let at = info
+                .location()
+                .map(|l| format!(" at {l}"))
+                .unwrap_or_default();
+            let msg = info.payload_as_str().unwrap_or("Box<dyn Any>");
+            PANIC.set(Some(format!("panicked{at}: {msg}")));
+        }));
+    });
+}
+
+fn message(payload: &dyn Any) -> String {
+    let msg = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or(payload.downcast_ref::<String>().map(String::as_str));
+    format!("panicked: {}", msg.unwrap_or("Box<dyn Any>"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn isolation() {
+        let mut ctx = RunContext {
+            timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+        ctx.testing = true;
+        assert_eq!(isolated(&ctx, |ctx| ctx.testing), Ok(true));
+        let err = isolated(&ctx, |_| -> u8 { panic!("boom") }).unwrap_err();
+        assert!(err.starts_with("panicked at src/isolate.rs:"), "{err}");
+        assert!(err.ends_with(": boom"), "{err}");
+        let err = isolated(&ctx, |_| thread::sleep(Duration::from_secs(5))).unwrap_err();
+        assert_eq!(err, "timed out after 50ms");
+    }
+
+    #[test]
+    fn deep_recursion() {
+        fn depth(n: u64) -> u64 {
+            let frame = std::hint::black_box([n as u8; 1024]);
+            if n == 0 {
+                0
+            } else {
+                depth(n - 1) + (frame[1023] == n as u8) as u64
+            }
+        }
+        let ctx = RunContext::default();
+        assert_eq!(isolated(&ctx, |_| depth(20_000)), Ok(20_000));
+    }
+}
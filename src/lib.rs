@@ -1,58 +1,289 @@
 pub use std::collections::{HashMap, HashSet};
-use std::sync::atomic::{AtomicBool, Ordering};
 
+mod alloc;
+#[doc(hidden)]
+pub mod answer;
+mod cli;
 mod collections;
+mod config;
+mod context;
+mod extract;
+mod fetch;
+pub mod http;
+mod input;
+mod isolate;
+pub mod json;
 mod maps;
+mod registry;
+mod regression;
+mod runner;
+mod scaffold;
+mod solution;
 mod strings;
+mod submit;
+mod timing;
 mod unformat;
+mod watch;
 
+pub use alloc::*;
+pub use cli::*;
 pub use collections::*;
+pub use config::*;
+pub use context::*;
+pub use extract::*;
+pub use fetch::*;
+pub use http::HttpError;
+pub use input::*;
+pub use isolate::*;
+pub use json::Json;
 pub use maps::*;
+pub use registry::*;
+pub use regression::*;
+pub use runner::*;
+pub use scaffold::*;
+pub use solution::*;
 pub use strings::*;
-
-pub static TESTING: AtomicBool = AtomicBool::new(false);
-
-pub fn inp() -> String {
-    let mut path = std::env::args()
-        .nth(1)
-        .unwrap_or("inp/test.txt".to_string());
-    if path.starts_with("t") || path.starts_with("T") {
-        path = "inp/test.txt".to_string();
-    } else if let Ok(day) = path.parse::<u8>() {
-        path = format!("inp/{day}.txt");
-    }
-    if path.contains("test") {
-        TESTING.store(true, Ordering::Relaxed);
-    }
-    std::fs::read_to_string(path).unwrap()
-}
+pub use submit::*;
+pub use timing::*;
+pub use watch::*;
 
 #[macro_export]
 macro_rules! test {
     () => {
-        TESTING.load(std::sync::atomic::Relaxed)
+        $crate::RunContext::with(|ctx| ctx.testing)
     };
     ($test:expr => $real:expr) => {
         if $crate::test!() { $test } else { $real }
     };
 }
 
+/// `eprintln!` with the location, when running an example or with `-v`
+///
+/// Compiled away in release builds.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        $crate::__aoc_log!(1, $($arg)*)
+    };
+}
+
+/// Like [`debug!`], but needs `-vv`, or `-v` on an example
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        $crate::__aoc_log!(2, $($arg)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __aoc_log {
+    ($level:literal, $($arg:tt)*) => {
+        if cfg!(debug_assertions) && $crate::RunContext::with(|ctx| ctx.shows($level)) {
+            eprintln!("[{}:{}] {}", file!(), line!(), format_args!($($arg)*));
+        }
+    };
+}
+
+/// Generate `main` for a solution
+///
+/// `aoc!(part1, part2 as Data)` parses the input with `input` and passes
+/// it to the parts by value. With `as clone Data`, running both parts
+/// parses once and clones, with `as ref Data` the parts take `&Data`.
+/// Any of `input`, `part1` and `part2` may take a `&RunContext` as
+/// additional last argument, and may return a `Result` whose error is
+/// reported with the failing day, phase and input.
+///
+/// Options follow after a `;`:
+/// - `examples { "test.txt" => (357, 3121910778619) }` declares the expected
+///   answers for example inputs in `inp/`, which are checked before running
+///   the real input. Use `(357)` or `(_, 42)` for a single part.
+///   Answers are literals, written the way they are printed.
+///   `part2: "test2.txt" => 42` also makes `test2.txt` the example
+///   part 2 runs on when no example number is given.
+///   The examples also become tests, run by `cargo test`, which fail for
+///   a part no example declares an answer for.
+/// - `params { steps: u32 = 6 => 64 }` declares puzzle parameters with their
+///   value for examples and for the real input. `Params::get()` returns them
+///   for the current run, `--param steps=10` overrides them.
+///
+/// `aoc!(part1, part2 | part2_naive as Data)` adds `part2_naive` as another
+/// implementation of part 2. The tests check it on the examples too, and
+/// `--cross-check` runs both on the examples and the input, comparing
+/// answers and times.
+///
+/// `aoc_runner()` returns the generated [`Runner`], which implements
+/// [`Solution`] for calling the parts from code, `day()` boxes it for [`days!`].
 #[macro_export]
 macro_rules! aoc {
-    ($part1:ident $(, $part2:ident)? $(as $inp:ty)?) => {
+    (@main [$mode:ident, $arg:ty $(, $share:ident)?] [$($item:item)*] $data:ty, $parse:expr,
+        $part1:ident $(| $alt1:ident)* $(, $part2:ident $(| $alt2:ident)*)?; $($opt:tt)*) => {
         use $crate::*;
 
-        $(type Data = $inp;)?
+        $($item)*
+
+        $crate::aoc!(@items $($opt)*);
+
+        fn aoc_runner() -> $crate::Runner<$data> {
+            // only options change the runner after it is built
+            #[allow(unused_mut)]
+            let mut runner = $crate::Runner::new($parse)
+                $(.$share())?
+                .part1($crate::aoc!(@part $mode, $arg, $part1))
+                $(.alt(1, stringify!($alt1), $crate::aoc!(@part $mode, $arg, $alt1)))*
+                $(
+                    .part2($crate::aoc!(@part $mode, $arg, $part2))
+                    $(.alt(2, stringify!($alt2), $crate::aoc!(@part $mode, $arg, $alt2)))*
+                )?;
+            $crate::aoc!(@opts runner $($opt)*);
+            runner
+        }
+
+        /// This day for a [`Registry`]($crate::Registry)
+        pub fn day() -> Box<dyn $crate::Day> {
+            Box::new(aoc_runner())
+        }
 
+        // unused when the day is registered with `days!`
+        #[allow(dead_code)]
         fn main() {
-            let data = $crate::inp();
-            $(let data: $inp = input(data);)?
-            let res = $(if std::env::args().nth(2).is_some() {
-                $part2(data).to_string()
-            } else )? {
-                $part1(data).to_string()
-            };
-            println!("result = {res}");
+            $crate::set_crate(env!("CARGO_PKG_NAME"), env!("CARGO_MANIFEST_DIR"));
+            day().run(&$crate::Args::parse());
+        }
+
+        $crate::aoc!(@tests [$part1 $(, $part2)?] $($opt)*);
+    };
+    // tests only for days that declare examples
+    (@tests [$($part:ident),*]) => {};
+    (@tests [$part1:ident $(, $part2:ident)?] examples { $($_:tt)* } $($rest:tt)*) => {
+        #[cfg(test)]
+        mod aoc_examples {
+            #[test]
+            fn $part1() {
+                super::aoc_runner().test_examples(1);
+            }
+
+            $(
+                #[test]
+                fn $part2() {
+                    super::aoc_runner().test_examples(2);
+                }
+            )?
+        }
+    };
+    (@tests [$($part:ident),*] $_:tt $($rest:tt)*) => {
+        $crate::aoc!(@tests [$($part),*] $($rest)*);
+    };
+    (@part $mode:ident, $arg:ty, $part:ident) => {
+        $crate::PartFn::$mode(|data: $arg, ctx: &$crate::RunContext| {
+            $crate::fallible!($crate::CtxFn::call_ctx(&$part, data, ctx)).map(|a| a.to_string())
+        })
+    };
+    (@items) => {};
+    (@items examples { $($_:tt)* } $($rest:tt)*) => {
+        $crate::aoc!(@items $($rest)*);
+    };
+    (@items params { $($name:ident: $t:ty = $test:expr => $real:expr),* $(,)? } $($rest:tt)*) => {
+        /// Puzzle parameters declared in [`aoc!`]
+        #[allow(dead_code)]
+        struct Params {
+            $($name: $t,)*
+        }
+
+        impl Params {
+            /// The parameters of the current run, the declared values for
+            /// `testing` when called outside the runner
+            #[allow(dead_code)]
+            fn get() -> Self {
+                $crate::RunContext::with(|ctx| Self {
+                    $($name: ctx.param::<$t>(stringify!($name))
+                        .unwrap_or_else(|| if ctx.testing { $test } else { $real }),)*
+                })
+            }
+        }
+
+        $crate::aoc!(@items $($rest)*);
+    };
+    (@opts $runner:ident) => {};
+    (@opts $runner:ident params { $($name:ident: $t:ty = $test:expr => $real:expr),* $(,)? }
+        $($rest:tt)*) => {
+        $($runner = $runner.param::<$t>(stringify!($name), $test, $real);)*
+        $crate::aoc!(@opts $runner $($rest)*);
+    };
+    (@opts $runner:ident examples { $($ex:tt)* } $($rest:tt)*) => {
+        $crate::aoc!(@examples $runner $($ex)*);
+        $crate::aoc!(@opts $runner $($rest)*);
+    };
+    (@examples $runner:ident) => {};
+    (@examples $runner:ident part1: $file:literal => $($p:literal)? $(_)? $(, $($rest:tt)*)?) => {
+        $runner = $runner.example($file, [$crate::aoc!(@ans $($p)?), None]).bind(1, $file);
+        $crate::aoc!(@examples $runner $($($rest)*)?);
+    };
+    (@examples $runner:ident part2: $file:literal => $($p:literal)? $(_)? $(, $($rest:tt)*)?) => {
+        $runner = $runner.example($file, [None, $crate::aoc!(@ans $($p)?)]).bind(2, $file);
+        $crate::aoc!(@examples $runner $($($rest)*)?);
+    };
+    (@examples $runner:ident $file:literal => ($($p1:literal)? $(_)?, $($p2:literal)? $(_)?)
+        $(, $($rest:tt)*)?) => {
+        $runner = $runner.example($file, [$crate::aoc!(@ans $($p1)?), $crate::aoc!(@ans $($p2)?)]);
+        $crate::aoc!(@examples $runner $($($rest)*)?);
+    };
+    (@examples $runner:ident $file:literal => $(($p1:literal))? $($p1_:literal)? $(, $($rest:tt)*)?) => {
+        $runner = $runner.example($file, [$crate::aoc!(@ans $($p1)? $($p1_)?), None]);
+        $crate::aoc!(@examples $runner $($($rest)*)?);
+    };
+    (@ans) => {
+        None
+    };
+    (@ans $answer:literal) => {
+        Some($crate::literal_answer(stringify!($answer)))
+    };
+    ($part1:ident $(| $alt1:ident)* $(, $part2:ident $(| $alt2:ident)*)? as ref $inp:ty $(; $($opt:tt)*)?) => {
+        $crate::aoc!(@main [by_ref, &Data] [type Data = $inp;] Data, |raw: String, ctx: &$crate::RunContext| -> Result<Data, String> {
+            $crate::fallible!($crate::CtxFn::call_ctx(&input, raw, ctx))
+        },
+            $part1 $(| $alt1)* $(, $part2 $(| $alt2)*)?; $($($opt)*)?);
+    };
+    ($part1:ident $(| $alt1:ident)* $(, $part2:ident $(| $alt2:ident)*)? as clone $inp:ty $(; $($opt:tt)*)?) => {
+        $crate::aoc!(@main [owned, Data, cloned] [type Data = $inp;] Data, |raw: String, ctx: &$crate::RunContext| -> Result<Data, String> {
+            $crate::fallible!($crate::CtxFn::call_ctx(&input, raw, ctx))
+        },
+            $part1 $(| $alt1)* $(, $part2 $(| $alt2)*)?; $($($opt)*)?);
+    };
+    ($part1:ident $(| $alt1:ident)* $(, $part2:ident $(| $alt2:ident)*)? as $inp:ty $(; $($opt:tt)*)?) => {
+        $crate::aoc!(@main [owned, Data] [type Data = $inp;] Data, |raw: String, ctx: &$crate::RunContext| -> Result<Data, String> {
+            $crate::fallible!($crate::CtxFn::call_ctx(&input, raw, ctx))
+        },
+            $part1 $(| $alt1)* $(, $part2 $(| $alt2)*)?; $($($opt)*)?);
+    };
+    ($part1:ident $(| $alt1:ident)* $(, $part2:ident $(| $alt2:ident)*)? $(; $($opt:tt)*)?) => {
+        $crate::aoc!(@main [owned, String] [] String, |raw: String, _: &$crate::RunContext| Ok(raw), $part1 $(| $alt1)* $(, $part2 $(| $alt2)*)?; $($($opt)*)?);
+    };
+}
+
+/// Build a binary running several days
+///
+/// ```ignore
+/// aoc::days! {
+///     1 => day01,
+///     3 => day03,
+/// }
+/// ```
+///
+/// Every day is a module in `src/days/` written with [`aoc!`].
+/// The binary runs `aoc 3`, `aoc 1..12` or `aoc all`, see [`Registry`].
+#[macro_export]
+macro_rules! days {
+    ($($day:literal => $module:ident),* $(,)?) => {
+        mod days {
+            $(pub mod $module;)*
+        }
+
+        fn main() {
+            $crate::set_crate(env!("CARGO_PKG_NAME"), env!("CARGO_MANIFEST_DIR"));
+            $crate::Registry::new()
+                $(.day($day, days::$module::day))*
+                .run(&$crate::Args::parse());
         }
     };
 }
@@ -71,6 +302,7 @@ macro_rules! cmp_macro {
 cmp_macro! [eq ne le lt ge gt];
 
 pub trait BoolExt {
+    #[allow(clippy::wrong_self_convention)]
     fn as_sign(self) -> i64;
 }
 
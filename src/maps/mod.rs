@@ -14,6 +14,7 @@ use crate::{SliceExt as _, maps::iter::PosIter};
 
 pub type Pos = (usize, usize);
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Map2D<T>(pub Vec<Vec<T>>);
 
 impl<T> Map2D<T> {
@@ -0,0 +1,231 @@
+use std::{
+    ops::RangeInclusive,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Args, Command, Format, Json, Part, Runner, Source, Status};
+
+/// A day that a [`Registry`] can run
+pub trait Day {
+    /// Run the day on its own, as its `main` would
+    fn run(&self, args: &Args);
+
+    /// Run all parts on the real input of `args.day` for a summary
+    fn report(&self, args: &Args) -> Report;
+}
+
+impl<D: Send + Sync + 'static> Day for Runner<D> {
+    fn run(&self, args: &Args) {
+        Runner::run(self, args);
+    }
+
+    fn report(&self, args: &Args) -> Report {
+        Runner::report(self, args)
+    }
+}
+
+/// Outcome of a day in a summary table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub day: u8,
+    pub parse: Duration,
+    pub parts: [(Status, Duration); 2],
+    /// one object per part, or for the failed parsing, as a day run
+    /// with `--format json` prints them
+    pub json: Vec<Json>,
+}
+
+impl Report {
+    pub fn new(day: u8) -> Self {
+        Self {
+            day,
+            parse: Duration::ZERO,
+            parts: [(Status::Missing, Duration::ZERO); 2],
+            json: Vec::new(),
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.parse + self.parts[0].1 + self.parts[1].1
+    }
+}
+
+type MakeFn = fn() -> Box<dyn Day>;
+
+/// The days of a multi-day binary, see [`days!`](crate::days!)
+#[derive(Default)]
+pub struct Registry {
+    days: Vec<(u8, MakeFn)>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn day(mut self, day: u8, make: MakeFn) -> Self {
+        self.days.push((day, make));
+        self
+    }
+
+    /// Run the day selected by `args`, or several days for `all` and ranges
+    ///
+    /// Without a day, the highest registered day runs, so `cargo run -- t`
+    /// runs the example of the day being worked on.
+    pub fn run(&self, args: &Args) {
+        if let Some(Command::All(range)) = &args.command {
+            self.run_days(args, range.clone());
+            return;
+        }
+        if let Some(cmd) = &args.command {
+            cmd.run(args);
+            return;
+        }
+        let days: Vec<_> = self.days.iter().map(|(day, _)| day.to_string()).collect();
+        let Some(day) = args.day.or(self.days.iter().map(|(day, _)| *day).max()) else {
+            eprintln!("error: no days registered");
+            std::process::exit(2);
+        };
+        match self.days.iter().find(|(d, _)| *d == day) {
+            Some((_, make)) => make().run(&Args {
+                day: Some(day),
+                ..args.clone()
+            }),
+            None => {
+                eprintln!(
+                    "error: day {day} is not solved yet, available: {}",
+                    days.join(", ")
+                );
+                std::process::exit(2);
+            }
+        }
+    }
+
+    /// Report on the selected days, `args.jobs` at a time, and print a table
+    ///
+    /// Exits with code 1 if a part failed, or 3 if an answer is wrong.
+    fn run_days(&self, args: &Args, range: Option<RangeInclusive<u8>>) {
+        let start = Instant::now();
+        let reports = self.reports(args, range);
+        let wall = start.elapsed();
+        let jobs = args.jobs.unwrap_or(1).clamp(1, reports.len().max(1));
+        let code = Status::exit_code(reports.iter().flat_map(|r| r.parts.map(|(s, _)| s)));
+        if args.format == Format::Json {
+            reports
+                .iter()
+                .flat_map(|r| &r.json)
+                .for_each(|json| println!("{json}"));
+            std::process::exit(code);
+        }
+        println!(
+            "{:>4}  {:10}{:10}{:>12}{:>12}{:>12}{:>12}",
+            "day", "part 1", "part 2", "parse", "time 1", "time 2", "total"
+        );
+        for r in &reports {
+            let [(s1, t1), (s2, t2)] = r.parts;
+            println!(
+                "{:>4}  {s1:10}{s2:10}{:>12.2?}{t1:>12.2?}{t2:>12.2?}{:>12.2?}",
+                r.day,
+                r.parse,
+                r.total()
+            );
+        }
+        let total: Duration = reports.iter().map(Report::total).sum();
+        println!("{:62}{total:>12.2?}", "total");
+        if jobs > 1 {
+            println!("{:62}{wall:>12.2?}", format!("wall time ({jobs} jobs)"));
+        }
+        std::process::exit(code);
+    }
+
+    /// Reports of the days in `range`, by day
+    fn reports(&self, args: &Args, range: Option<RangeInclusive<u8>>) -> Vec<Report> {
+        let mut days: Vec<_> = self
+            .days
+            .iter()
+            .filter(|(day, _)| range.as_ref().is_none_or(|r| r.contains(day)))
+            .copied()
+            .collect();
+        days.sort_by_key(|(day, _)| *day);
+        let jobs = args.jobs.unwrap_or(1).clamp(1, days.len().max(1));
+        let next = AtomicUsize::new(0);
+        let mut reports: Vec<Report> = thread::scope(|s| {
+            let workers: Vec<_> = (0..jobs)
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut done = Vec::new();
+                        while let Some(&(day, make)) =
+                            days.get(next.fetch_add(1, Ordering::Relaxed))
+                        {
+                            let args = Args {
+                                command: None,
+                                part: Part::Both,
+                                source: Source::Real,
+                                day: Some(day),
+                                ..args.clone()
+                            };
+                            done.push(make().report(&args));
+                        }
+                        done
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().unwrap())
+                .collect()
+        });
+        reports.sort_by_key(|r| r.day);
+        reports
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Fails part 2 on day 3, takes 50ms per day
+    struct Fake;
+
+    impl Day for Fake {
+        fn run(&self, _: &Args) {}
+
+        fn report(&self, args: &Args) -> Report {
+            thread::sleep(Duration::from_millis(50));
+            let mut report = Report::new(args.day.unwrap());
+            report.parts[0].0 = Status::Ok;
+            if report.day == 3 {
+                report.parts[1].0 = Status::Mismatch;
+            }
+            report
+        }
+    }
+
+    #[test]
+    fn days() {
+        let fake = || Box::new(Fake) as Box<dyn Day>;
+        let registry = (1..=8)
+            .rev()
+            .fold(Registry::new(), |r, day| r.day(day, fake));
+        let args = Args {
+            jobs: Some(4),
+            ..Args::default()
+        };
+        let start = Instant::now();
+        let reports = registry.reports(&args, Some(2..=5));
+        assert!(start.elapsed() < Duration::from_millis(150));
+        let days: Vec<_> = reports.iter().map(|r| r.day).collect();
+        assert_eq!(days, [2, 3, 4, 5]);
+        let statuses = |reports: &[Report]| -> Vec<_> {
+            reports
+                .iter()
+                .flat_map(|r| r.parts.map(|(s, _)| s))
+                .collect()
+        };
+        assert_eq!(Status::exit_code(statuses(&reports)), 3);
+        assert_eq!(Status::exit_code(statuses(&reports[2..])), 0);
+        assert_eq!(registry.reports(&args, None).len(), 8);
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+    path::PathBuf,
+};
+
+/// Accepted answers, one tab-separated `year day part answer` line each
+///
+/// Filled by correct submissions and `--accept`, every run on a real
+/// input is compared against it.
+pub struct AnswerStore {
+    pub path: PathBuf,
+    pub answers: BTreeMap<(u16, u8, u8), String>,
+}
+
+impl AnswerStore {
+    pub const FILE: &str = "answers.txt";
+
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let answers = std::fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .flat_map(|line| {
+                let mut cols = line.splitn(4, '\t');
+                let key = (
+                    cols.next()?.parse().ok()?,
+                    cols.next()?.parse().ok()?,
+                    cols.next()?.parse().ok()?,
+                );
+                Some((key, cols.next()?.to_string()))
+            })
+            .collect();
+        Self { path, answers }
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(year, day, part)).map(String::as_str)
+    }
+
+    /// Remember an answer and write the file
+    pub fn accept(&mut self, year: u16, day: u8, part: u8, answer: &str) -> std::io::Result<()> {
+        self.answers.insert((year, day, part), answer.to_string());
+        let text: String = self
+            .answers
+            .iter()
+            .map(|((year, day, part), answer)| format!("{year}\t{day}\t{part}\t{answer}\n"))
+            .collect();
+        std::fs::write(&self.path, text)
+    }
+
+    /// How `answer` compares to the accepted one
+    pub fn status(&self, year: Option<u16>, day: u8, part: u8, answer: &str) -> Status {
+        match year.and_then(|year| self.get(year, day, part)) {
+            Some(acc) if acc == answer => Status::Ok,
+            Some(_) => Status::Mismatch,
+            None => Status::New,
+        }
+    }
+
+    /// Days of `year` with an accepted answer
+    pub fn days(&self, year: u16) -> BTreeSet<u8> {
+        self.answers
+            .keys()
+            .filter(|k| k.0 == year)
+            .map(|k| k.1)
+            .collect()
+    }
+}
+
+/// Outcome of one part when running several days
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Mismatch,
+    New,
+    Error,
+    Missing,
+}
+
+impl Status {
+    pub fn failed(self) -> bool {
+        matches!(self, Status::Mismatch | Status::Error)
+    }
+
+    /// The exit code of a run with these outcomes: 1 if a part gave no
+    /// answer, else 3 if an answer is wrong, else 0
+    pub fn exit_code(statuses: impl IntoIterator<Item = Status>) -> i32 {
+        let statuses: Vec<_> = statuses.into_iter().collect();
+        if statuses.contains(&Status::Error) {
+            1
+        } else if statuses.contains(&Status::Mismatch) {
+            3
+        } else {
+            0
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Status::Ok => "OK",
+            Status::Mismatch => "MISMATCH",
+            Status::New => "new",
+            Status::Error => "error",
+            Status::Missing => "-",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn store() {
+        let path = std::env::temp_dir().join(format!("aoc-answers-{}", std::process::id()));
+        let mut store = AnswerStore::load(&path);
+        store.accept(2025, 3, 2, "3121910778619").unwrap();
+        store.accept(2025, 3, 1, "357").unwrap();
+        let store = AnswerStore::load(&path);
+        assert_eq!(store.get(2025, 3, 1), Some("357"));
+        assert_eq!(store.get(2024, 3, 1), None);
+        assert_eq!(store.days(2025), BTreeSet::from([3]));
+        assert_eq!(store.status(Some(2025), 3, 1, "357"), Status::Ok);
+        assert_eq!(store.status(Some(2025), 3, 2, "12"), Status::Mismatch);
+        assert_eq!(store.status(None, 3, 1, "357"), Status::New);
+        assert_eq!(Status::exit_code([Status::Ok, Status::New]), 0);
+        assert_eq!(Status::exit_code([Status::Mismatch, Status::Ok]), 3);
+        assert_eq!(Status::exit_code([Status::Mismatch, Status::Error]), 1);
+        std::fs::remove_file(path).unwrap();
+    }
+}
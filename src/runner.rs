@@ -0,0 +1,993 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    AnswerStore, Args, Format, Json, Part, Report, RunContext, Snapshot, Solution, Source, Stats,
+    Status, Usage, isolated, load_input, normalize, prepare, read_source, rebuild, submit_answer,
+};
+
+/// The answer of a part, or why it failed
+pub type Answer = Result<String, String>;
+
+type OwnedFn<D> = Arc<dyn Fn(D, &RunContext) -> Answer + Send + Sync>;
+type RefFn<D> = Arc<dyn Fn(&D, &RunContext) -> Answer + Send + Sync>;
+type ParseFn<D> = Arc<dyn Fn(String, &RunContext) -> Result<D, String> + Send + Sync>;
+/// Selected parts, grouped by the input they run on
+type Inputs<'a, D> = [(String, Vec<(u8, &'a PartFn<D>)>)];
+
+/// A single part of a solution
+pub enum PartFn<D> {
+    /// consumes the parsed input
+    Owned(OwnedFn<D>),
+    /// borrows the parsed input
+    Ref(RefFn<D>),
+}
+
+impl<D> PartFn<D> {
+    pub fn owned(f: impl Fn(D, &RunContext) -> Answer + Send + Sync + 'static) -> Self {
+        PartFn::Owned(Arc::new(f))
+    }
+
+    pub fn by_ref(f: impl Fn(&D, &RunContext) -> Answer + Send + Sync + 'static) -> Self {
+        PartFn::Ref(Arc::new(f))
+    }
+
+    pub fn call(&self, data: D, ctx: &RunContext) -> Answer {
+        match self {
+            PartFn::Owned(f) => f(data, ctx),
+            PartFn::Ref(f) => f(&data, ctx),
+        }
+    }
+}
+
+/// Expected answers for an example input
+pub struct Example {
+    /// file name inside `inp/`, or a path
+    pub file: String,
+    pub expected: [Option<String>; 2],
+}
+
+impl Example {
+    pub fn path(&self) -> String {
+        example_path(&self.file)
+    }
+}
+
+fn example_path(file: &str) -> String {
+    if file.contains('/') {
+        file.to_string()
+    } else {
+        format!("inp/{file}")
+    }
+}
+
+/// The answer a literal in [`aoc!`](crate::aoc!) stands for
+///
+/// Strips quotes, digit separators and integer suffixes,
+/// so `"ABC"` is `ABC` and `1_000u64` is `1000`.
+#[doc(hidden)]
+pub fn literal_answer(lit: &str) -> String {
+    if let Some(s) = lit.strip_prefix('"').and_then(|l| l.strip_suffix('"')) {
+        return s.replace("\\\"", "\"");
+    }
+    if let Some(s) = lit.strip_prefix('\'').and_then(|l| l.strip_suffix('\'')) {
+        return s.to_string();
+    }
+    let end = lit
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(lit.len());
+    lit[..end].replace(['_', ' '], "")
+}
+
+/// A named puzzle parameter with its values for examples and the real input
+struct Param {
+    name: String,
+    test: String,
+    real: String,
+    valid: fn(&str) -> bool,
+}
+
+struct Solved {
+    nr: u8,
+    answer: Answer,
+    time: Duration,
+    usage: Option<Usage>,
+}
+
+/// Glue between the command line and a solution, built by [`aoc!`](crate::aoc!)
+///
+/// When both parts run, the input is parsed once and shared:
+/// parts taking `&D` borrow it, parts taking `D` get a clone
+/// (see [`cloned`](Runner::cloned)) or, without one, a fresh parse.
+/// Parsing and every part run on their own thread, see [`isolated`].
+pub struct Runner<D> {
+    parse: ParseFn<D>,
+    dup: Option<fn(&D) -> D>,
+    parts: [Option<PartFn<D>>; 2],
+    alts: [Vec<(String, PartFn<D>)>; 2],
+    examples: Vec<Example>,
+    bound: [Option<String>; 2],
+    params: Vec<Param>,
+}
+
+impl<D: Send + Sync + 'static> Runner<D> {
+    pub fn new(
+        parse: impl Fn(String, &RunContext) -> Result<D, String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            parse: Arc::new(parse),
+            dup: None,
+            parts: [None, None],
+            alts: [Vec::new(), Vec::new()],
+            examples: Vec::new(),
+            bound: [None, None],
+            params: Vec::new(),
+        }
+    }
+
+    /// Share the parsed input by cloning instead of parsing again
+    pub fn cloned(mut self) -> Self
+    where
+        D: Clone,
+    {
+        self.dup = Some(D::clone);
+        self
+    }
+
+    pub fn part1(mut self, f: PartFn<D>) -> Self {
+        self.parts[0] = Some(f);
+        self
+    }
+
+    pub fn part2(mut self, f: PartFn<D>) -> Self {
+        self.parts[1] = Some(f);
+        self
+    }
+
+    /// Add another implementation of part `nr`, which must give the same
+    /// answers, see `--cross-check`
+    pub fn alt(mut self, nr: u8, name: &str, f: PartFn<D>) -> Self {
+        self.alts[nr as usize - 1].push((name.to_string(), f));
+        self
+    }
+
+    /// Declare the expected answers for an example input
+    pub fn example(mut self, file: &str, expected: [Option<String>; 2]) -> Self {
+        self.examples.push(Example {
+            file: file.to_string(),
+            expected,
+        });
+        self
+    }
+
+    /// Use example `file` for part `nr` when running on the default example
+    pub fn bind(mut self, nr: u8, file: &str) -> Self {
+        self.bound[nr as usize - 1] = Some(file.to_string());
+        self
+    }
+
+    /// Declare a parameter, overridable by `--param name=value`
+    pub fn param<T>(mut self, name: &str, test: T, real: T) -> Self
+    where
+        T: FromStr + Display,
+    {
+        self.params.push(Param {
+            name: name.to_string(),
+            test: test.to_string(),
+            real: real.to_string(),
+            valid: |val| val.parse::<T>().is_ok(),
+        });
+        self
+    }
+
+    /// Run the parts selected by `args`
+    ///
+    /// Before running on anything but an example, all declared
+    /// examples are checked and the run is aborted if one fails.
+    /// Parts bound to different examples are run on their own input.
+    /// Exits with code 1 if a part fails, or 3 if an answer is wrong.
+    pub fn run(&self, args: &Args) {
+        if let Some(cmd) = &args.command {
+            cmd.run(args);
+            return;
+        }
+        if args.part == Part::Two && self.parts[1].is_none() {
+            eprintln!("error: part 2 is not implemented yet");
+            std::process::exit(2);
+        }
+        let selected: Vec<_> = (1..)
+            .zip(&self.parts)
+            .filter(|(nr, _)| args.part.runs(*nr))
+            .flat_map(|(nr, part)| Some((nr, part.as_ref()?)))
+            .collect();
+        if let Err(e) = self.check_params(args) {
+            eprintln!("error: {e}");
+            std::process::exit(2);
+        }
+        if args.cross_check {
+            std::process::exit(Status::exit_code(self.cross_check(args, &selected)));
+        }
+        let mut inputs: Vec<(String, Vec<_>)> = Vec::new();
+        for &(nr, part) in &selected {
+            let path = self.input_path(args, nr);
+            match inputs.iter_mut().find(|(p, _)| *p == path) {
+                Some((_, parts)) => parts.push((nr, part)),
+                None => inputs.push((path, vec![(nr, part)])),
+            }
+        }
+        if args.watch {
+            self.watch(args, &inputs);
+        }
+        if !args.testing() && !args.no_check {
+            let code = Status::exit_code(self.check_examples(args, &selected));
+            if code != 0 {
+                eprintln!("error: examples failed, not running the real input");
+                std::process::exit(code);
+            }
+        }
+        let mut statuses = Vec::new();
+        let mut store = AnswerStore::load(AnswerStore::FILE);
+        for (path, parts) in inputs {
+            let raw = load_input(args, &path).unwrap_or_else(|e| {
+                eprintln!("error: {e}");
+                std::process::exit(1);
+            });
+            let example = self.examples.iter().find(|ex| ex.path() == path);
+            let mut ctx = self.context(args, &path, args.testing());
+            let (parse, parse_usage, solved) = match self.solve(&mut ctx, &parts, raw.clone()) {
+                Ok(res) => res,
+                Err(e) => {
+                    if args.format == Format::Json {
+                        let failed = Err(e.clone());
+                        let none = Duration::ZERO;
+                        let json =
+                            result_json(&ctx, None, &failed, Status::Error, none, none, None);
+                        println!("{json}");
+                    }
+                    eprintln!("error ({}): {e}", location(&ctx, None));
+                    std::process::exit(1);
+                }
+            };
+            let mut times = vec![format!("parse {parse:.2?}")];
+            let mut usages: Vec<_> = parse_usage
+                .map(|u| format!("parse {u}"))
+                .into_iter()
+                .collect();
+            for Solved {
+                nr,
+                answer,
+                time,
+                usage,
+            } in solved
+            {
+                if args.format == Format::Json {
+                    let status = status(&ctx, example, &store, nr, &answer);
+                    let json = result_json(&ctx, Some(nr), &answer, status, parse, time, usage);
+                    println!("{json}");
+                }
+                let answer = match answer {
+                    Ok(answer) => answer,
+                    Err(e) => {
+                        eprintln!("error ({}): {e}", location(&ctx, Some(nr)));
+                        statuses.push(Status::Error);
+                        continue;
+                    }
+                };
+                let key = ctx.year.zip(ctx.day).filter(|_| !ctx.testing);
+                let accepted = key.and_then(|(year, day)| store.get(year, day, nr));
+                let check = match example.and_then(|ex| ex.expected[nr as usize - 1].as_ref()) {
+                    Some(exp) if *exp == answer => " (ok)".to_string(),
+                    Some(exp) => {
+                        statuses.push(Status::Mismatch);
+                        format!(" (expected {exp})")
+                    }
+                    None => match accepted {
+                        Some(acc) if acc == answer => " (OK)".to_string(),
+                        Some(acc) => {
+                            statuses.push(Status::Mismatch);
+                            format!(" (MISMATCH, accepted {acc})")
+                        }
+                        None => String::new(),
+                    },
+                };
+                match (args.format, args.part) {
+                    (Format::Json, _) => {}
+                    (Format::Text, Part::Both) => println!("part {nr} = {answer}{check}"),
+                    (Format::Text, _) => println!("result = {answer}{check}"),
+                }
+                if args.submit && !ctx.testing {
+                    submit_answer(args, nr, &answer);
+                }
+                if let (true, Some((year, day))) = (args.accept, key) {
+                    match store.accept(year, day, nr, &answer) {
+                        Ok(()) => args.note(format!("accepted part {nr}")),
+                        Err(e) => eprintln!("error: cannot write {}: {e}", AnswerStore::FILE),
+                    }
+                }
+                times.push(format!("part {nr} {time:.2?}"));
+                usages.extend(usage.map(|u| format!("part {nr} {u}")));
+            }
+            if args.format == Format::Text {
+                println!("time: {}", times.join(" | "));
+                if !usages.is_empty() {
+                    println!("memory: {}", usages.join(" | "));
+                }
+            }
+            if let Some(n) = args.bench {
+                self.bench(args, &mut ctx, n, &parts, raw);
+            }
+        }
+        let code = Status::exit_code(statuses);
+        if code != 0 {
+            std::process::exit(code);
+        }
+    }
+
+    /// Run the inputs again whenever they change, and rebuild and
+    /// restart when a source file in `src/` changes
+    fn watch(&self, args: &Args, inputs: &Inputs<D>) -> ! {
+        let paths = || inputs.iter().map(|(path, _)| PathBuf::from(path));
+        let mut prev = HashMap::new();
+        let mut seen = Snapshot::of(paths());
+        let sources = Snapshot::sources("src");
+        println!(
+            "watching {} input(s) and src/, ctrl-c to stop",
+            inputs.len()
+        );
+        self.watch_round(args, inputs, &mut prev);
+        loop {
+            thread::sleep(Duration::from_millis(300));
+            let changed = Snapshot::sources("src");
+            if let Some(file) = changed.changed(&sources).first() {
+                println!("\n{} changed, rebuilding", file.display());
+                if let Err(e) = rebuild() {
+                    eprintln!("error: {e}");
+                }
+                // wait for the next change of the sources before trying again
+                while Snapshot::sources("src") == changed {
+                    thread::sleep(Duration::from_millis(300));
+                }
+                continue;
+            }
+            let now = Snapshot::of(paths());
+            if let Some(file) = now.changed(&seen).first() {
+                println!("\n{} changed", file.display());
+                self.watch_round(args, inputs, &mut prev);
+                seen = now;
+            }
+        }
+    }
+
+    /// Run all inputs, printing how the answers differ from `prev`
+    fn watch_round(
+        &self,
+        args: &Args,
+        inputs: &Inputs<D>,
+        prev: &mut HashMap<(String, u8), String>,
+    ) {
+        for (path, parts) in inputs {
+            let raw = match load_input(args, path) {
+                Ok(raw) => raw,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    continue;
+                }
+            };
+            let example = self.examples.iter().find(|ex| ex.path() == *path);
+            let mut ctx = self.context(args, path, args.testing());
+            let solved = match self.solve(&mut ctx, parts, raw) {
+                Ok((_, _, solved)) => solved,
+                Err(e) => {
+                    eprintln!("error ({}): {e}", location(&ctx, None));
+                    continue;
+                }
+            };
+            for Solved {
+                nr, answer, time, ..
+            } in solved
+            {
+                let answer = answer.unwrap_or_else(|e| format!("error: {e}"));
+                let check = match example.and_then(|ex| ex.expected[nr as usize - 1].as_ref()) {
+                    Some(exp) if *exp == answer => " (ok)".to_string(),
+                    Some(exp) => format!(" (expected {exp})"),
+                    None => String::new(),
+                };
+                let diff = match prev.insert((path.clone(), nr), answer.clone()) {
+                    Some(old) if old != answer => format!(" (was {old})"),
+                    Some(_) => " (unchanged)".to_string(),
+                    None => String::new(),
+                };
+                println!("{path} part {nr} = {answer}{check}{diff} in {time:.2?}");
+            }
+        }
+    }
+
+    /// Context with the declared parameters filled in
+    fn context(&self, args: &Args, input: &str, testing: bool) -> RunContext {
+        let mut ctx = RunContext::new(args, input, testing);
+        for param in &self.params {
+            let default = if testing { &param.test } else { &param.real };
+            ctx.params
+                .entry(param.name.clone())
+                .or_insert_with(|| default.clone());
+        }
+        ctx
+    }
+
+    /// Check the values given with `--param`, warns about undeclared ones
+    fn check_params(&self, args: &Args) -> Result<(), String> {
+        for (name, val) in &args.params {
+            match self.params.iter().find(|p| p.name == *name) {
+                Some(param) if !(param.valid)(val) => {
+                    return Err(format!("invalid value `{val}` for parameter `{name}`"));
+                }
+                None if !self.params.is_empty() => {
+                    eprintln!("warning: parameter `{name}` is not declared");
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn input_path(&self, args: &Args, nr: u8) -> String {
+        if args.source == Source::Example(None) {
+            if let Some(file) = &self.bound[nr as usize - 1] {
+                return example_path(file);
+            }
+            if let Some(ex) = self.examples.first() {
+                return ex.path();
+            }
+        }
+        args.input_path().unwrap_or_else(|e| {
+            eprintln!("error: {e}");
+            std::process::exit(2);
+        })
+    }
+
+    /// Parse `raw` and run the given parts on it, returns the time and
+    /// allocations of parsing and the parts
+    fn solve(
+        &self,
+        ctx: &mut RunContext,
+        selected: &[(u8, &PartFn<D>)],
+        raw: String,
+    ) -> Result<(Duration, Option<Usage>, Vec<Solved>), String> {
+        ctx.part = None;
+        ctx.enter();
+        let (parse_fn, input) = (self.parse.clone(), raw.clone());
+        let (data, parse, parse_usage) = isolated(ctx, move |ctx| timed(|| parse_fn(input, ctx)))?;
+        let mut data = Some(Arc::new(data?));
+        let mut solved = Vec::new();
+        for (idx, &(nr, part)) in selected.iter().enumerate() {
+            ctx.part = Some(nr);
+            ctx.enter();
+            let res = match part {
+                PartFn::Ref(f) => {
+                    let (f, data) = (f.clone(), data.clone().unwrap());
+                    isolated(ctx, move |ctx| timed(|| f(&data, ctx)))
+                }
+                PartFn::Owned(f) => {
+                    // the last part takes the parsed input, unless a part that
+                    // timed out still holds on to it
+                    let shared = if idx + 1 == selected.len() {
+                        data.take()
+                    } else {
+                        data.clone()
+                    };
+                    let owned = Arc::try_unwrap(shared.unwrap())
+                        .or_else(|shared| self.fresh(&shared, &raw, ctx));
+                    let f = f.clone();
+                    owned.and_then(|data| isolated(ctx, move |ctx| timed(|| f(data, ctx))))
+                }
+            };
+            let (answer, time, usage) = res.unwrap_or_else(|e| (Err(e), Duration::ZERO, None));
+            solved.push(Solved {
+                nr,
+                answer,
+                time,
+                usage,
+            });
+        }
+        Ok((parse, parse_usage, solved))
+    }
+
+    /// Part `nr` and its alternatives, with their names
+    fn implementations<'a>(&'a self, nr: u8, part: &'a PartFn<D>) -> Vec<(String, &'a PartFn<D>)> {
+        let alts = self.alts[nr as usize - 1].iter();
+        std::iter::once((format!("part {nr}"), part))
+            .chain(alts.map(|(name, f)| (name.clone(), f)))
+            .collect()
+    }
+
+    /// Run every implementation of the selected parts on their examples
+    /// and the input, returns the failures and differing answers
+    fn cross_check(&self, args: &Args, selected: &[(u8, &PartFn<D>)]) -> Vec<Status> {
+        let mut failed = Vec::new();
+        for &(nr, part) in selected {
+            let impls = self.implementations(nr, part);
+            let mut paths: Vec<_> = self
+                .examples
+                .iter()
+                .filter(|ex| ex.expected[nr as usize - 1].is_some())
+                .map(Example::path)
+                .collect();
+            let path = self.input_path(args, nr);
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+            for path in paths {
+                let raw = match load_input(args, &path) {
+                    Ok(raw) => raw,
+                    Err(e) => {
+                        eprintln!("error: {e}");
+                        failed.push(Status::Error);
+                        continue;
+                    }
+                };
+                let example = self.examples.iter().find(|ex| ex.path() == path);
+                let mut ctx = self.context(args, &path, example.is_some() || args.testing());
+                let parts: Vec<_> = impls.iter().map(|&(_, f)| (nr, f)).collect();
+                let solved = match self.solve(&mut ctx, &parts, raw) {
+                    Ok((_, _, solved)) => solved,
+                    Err(e) => {
+                        eprintln!("error ({}): {e}", location(&ctx, None));
+                        failed.push(Status::Error);
+                        continue;
+                    }
+                };
+                // without a declared answer, the alternatives must agree with the part
+                let expected = example
+                    .and_then(|ex| ex.expected[nr as usize - 1].clone())
+                    .or_else(|| solved[0].answer.clone().ok());
+                // no ratios against a part that failed
+                let base =
+                    Some(solved[0].time).filter(|time| solved[0].answer.is_ok() && !time.is_zero());
+                println!("part {nr}, {path}");
+                for ((name, _), Solved { answer, time, .. }) in impls.iter().zip(solved) {
+                    let ratio = match base.filter(|_| answer.is_ok()) {
+                        Some(base) => format!("{:.2}x", time.as_secs_f64() / base.as_secs_f64()),
+                        None => "-".to_string(),
+                    };
+                    let check = match (answer, &expected) {
+                        (Err(e), _) => {
+                            failed.push(Status::Error);
+                            format!("error: {e}")
+                        }
+                        (Ok(answer), Some(exp)) if answer != *exp => {
+                            failed.push(Status::Mismatch);
+                            format!("{answer} (expected {exp})")
+                        }
+                        (Ok(answer), _) => answer,
+                    };
+                    println!("  {name:16}{time:>12.2?}{ratio:>9}  {check}");
+                }
+            }
+        }
+        failed
+    }
+
+    /// Check all declared examples, returns the failures and wrong answers
+    fn check_examples(&self, args: &Args, selected: &[(u8, &PartFn<D>)]) -> Vec<Status> {
+        let mut failed = Vec::new();
+        for ex in &self.examples {
+            let parts: Vec<_> = selected
+                .iter()
+                .filter(|(nr, _)| ex.expected[*nr as usize - 1].is_some())
+                .copied()
+                .collect();
+            if parts.is_empty() {
+                continue;
+            }
+            let Ok(raw) = read_source(&ex.path()).map(|raw| prepare(args, raw)) else {
+                eprintln!("warning: example {} not found", ex.path());
+                continue;
+            };
+            let mut ctx = self.context(args, &ex.path(), true);
+            let solved = match self.solve(&mut ctx, &parts, raw) {
+                Ok((_, _, solved)) => solved,
+                Err(e) => {
+                    failed.push(Status::Error);
+                    args.note(format!("example {}: FAIL\n  error: {e}", ex.file));
+                    continue;
+                }
+            };
+            for Solved { nr, answer, .. } in solved {
+                let exp = ex.expected[nr as usize - 1].as_ref().unwrap();
+                match answer {
+                    Ok(answer) if *exp == answer => {
+                        args.note(format!("example {} part {nr}: ok", ex.file))
+                    }
+                    Ok(answer) => {
+                        failed.push(Status::Mismatch);
+                        args.note(format!("example {} part {nr}: FAIL", ex.file));
+                        args.note(format!("  expected: {exp}\n  got:      {answer}"));
+                    }
+                    Err(e) => {
+                        failed.push(Status::Error);
+                        args.note(format!("example {} part {nr}: FAIL\n  error: {e}", ex.file));
+                    }
+                }
+            }
+        }
+        failed
+    }
+
+    /// Run all parts on the real input and compare with the accepted answers
+    ///
+    /// Errors are printed, answers are not.
+    pub fn report(&self, args: &Args) -> Report {
+        let day = args.day.expect("a report needs a day");
+        let selected = self.implemented();
+        let mut report = Report::new(day);
+        for &(nr, _) in &selected {
+            report.parts[nr as usize - 1].0 = Status::Error;
+        }
+        let path = self.input_path(args, 1);
+        let mut ctx = self.context(args, &path, false);
+        let none = Duration::ZERO;
+        let solved = load_input(args, &path)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| self.solve(&mut ctx, &selected, raw));
+        let (parse, solved) = match solved {
+            Ok((parse, _, solved)) => (parse, solved),
+            Err(e) => {
+                eprintln!("error ({}): {e}", location(&ctx, None));
+                let failed = Err(e);
+                let json = result_json(&ctx, None, &failed, Status::Error, none, none, None);
+                report.json.push(json);
+                return report;
+            }
+        };
+        report.parse = parse;
+        let store = AnswerStore::load(AnswerStore::FILE);
+        for Solved {
+            nr,
+            answer,
+            time,
+            usage,
+        } in solved
+        {
+            let status = status(&ctx, None, &store, nr, &answer);
+            if let Err(e) = &answer {
+                eprintln!("error ({}): {e}", location(&ctx, Some(nr)));
+            }
+            report.parts[nr as usize - 1] = (status, time);
+            let json = result_json(&ctx, Some(nr), &answer, status, parse, time, usage);
+            report.json.push(json);
+        }
+        report
+    }
+
+    fn implemented(&self) -> Vec<(u8, &PartFn<D>)> {
+        (1..)
+            .zip(&self.parts)
+            .flat_map(|(nr, part)| Some((nr, part.as_ref()?)))
+            .collect()
+    }
+
+    /// Answers of all implemented parts for an in-memory input
+    ///
+    /// The input is normalized and counts as an example, so `test!` and the
+    /// parameters take their example values.
+    pub fn answers(&self, input: &str) -> [Option<Answer>; 2] {
+        let selected = self.implemented();
+        let mut ctx = self.context(&Args::default(), "<str>", true);
+        let mut res = [None, None];
+        match self.solve(&mut ctx, &selected, normalize(input)) {
+            Ok((_, _, solved)) => {
+                for Solved { nr, answer, .. } in solved {
+                    res[nr as usize - 1] = Some(answer);
+                }
+            }
+            Err(e) => {
+                for (nr, _) in selected {
+                    res[nr as usize - 1] = Some(Err(e.clone()));
+                }
+            }
+        }
+        res
+    }
+
+    /// Assert that part `nr` and its alternatives give the declared answer
+    /// for every example
+    ///
+    /// This is what the tests generated by [`aoc!`](crate::aoc!) run.
+    pub fn test_examples(&self, nr: u8) {
+        let part = self.parts[nr as usize - 1].as_ref().unwrap();
+        assert!(
+            self.examples
+                .iter()
+                .any(|ex| ex.expected[nr as usize - 1].is_some()),
+            "no example declares an answer for part {nr}"
+        );
+        for ex in &self.examples {
+            let Some(exp) = &ex.expected[nr as usize - 1] else {
+                continue;
+            };
+            let raw = read_source(&ex.path()).unwrap_or_else(|e| panic!("{e}"));
+            let raw = normalize(&raw);
+            for (name, part) in self.implementations(nr, part) {
+                let mut ctx = self.context(&Args::default(), &ex.path(), true);
+                let answer = self
+                    .solve(&mut ctx, &[(nr, part)], raw.clone())
+                    .and_then(|(_, _, mut solved)| solved.remove(0).answer)
+                    .unwrap_or_else(|e| panic!("example {} {name}: {e}", ex.file));
+                assert_eq!(answer, *exp, "example {} {name}", ex.file);
+            }
+        }
+    }
+
+    fn bench(
+        &self,
+        args: &Args,
+        ctx: &mut RunContext,
+        n: usize,
+        selected: &[(u8, &PartFn<D>)],
+        raw: String,
+    ) {
+        args.note(format!("\nbench ({n} runs)\n{:8}{}", "", Stats::header()));
+        ctx.part = None;
+        ctx.enter();
+        let parse = Stats::measure(n, || raw.clone(), |raw| (self.parse)(raw, ctx));
+        args.note(format!("{:8}{parse}", "parse"));
+        let Ok(data) = (self.parse)(raw.clone(), ctx) else {
+            return;
+        };
+        for &(nr, part) in selected {
+            ctx.part = Some(nr);
+            ctx.enter();
+            let stats = match part {
+                PartFn::Ref(f) => Stats::measure(n, || &data, |data| f(data, ctx)),
+                PartFn::Owned(f) => Stats::measure(
+                    n,
+                    || self.fresh(&data, &raw, ctx).unwrap(),
+                    |data| f(data, ctx),
+                ),
+            };
+            args.note(format!("{:8}{stats}", format!("part {nr}")));
+        }
+    }
+
+    /// Run part `nr` in the current context, `None` if it is not implemented
+    fn call(&self, nr: u8, input: D) -> Option<Answer> {
+        let part = self.parts[nr as usize - 1].as_ref()?;
+        let mut ctx = RunContext::with(RunContext::clone);
+        ctx.part = Some(nr);
+        Some(part.call(input, &ctx))
+    }
+
+    /// An owned copy of the parsed input
+    fn fresh(&self, data: &D, raw: &str, ctx: &RunContext) -> Result<D, String> {
+        match self.dup {
+            Some(dup) => Ok(dup(data)),
+            None => (self.parse)(raw.to_string(), ctx),
+        }
+    }
+}
+
+/// Runs in the context of the current thread, see [`RunContext::enter`]
+impl<D: Send + Sync + 'static> Solution for Runner<D> {
+    type Input = D;
+
+    fn parse(&self, raw: &str) -> Result<D, String> {
+        let ctx = RunContext::with(RunContext::clone);
+        (self.parse)(raw.to_string(), &ctx)
+    }
+
+    fn part1(&self, input: D) -> Answer {
+        self.call(1, input).expect("part 1 is not set")
+    }
+
+    fn part2(&self, input: D) -> Option<Answer> {
+        self.call(2, input)
+    }
+}
+
+/// How an answer compares to the expected answer of the example, or
+/// else to the accepted answer
+fn status(
+    ctx: &RunContext,
+    example: Option<&Example>,
+    store: &AnswerStore,
+    nr: u8,
+    answer: &Answer,
+) -> Status {
+    let Ok(answer) = answer else {
+        return Status::Error;
+    };
+    match example.and_then(|ex| ex.expected[nr as usize - 1].as_ref()) {
+        Some(exp) if exp == answer => Status::Ok,
+        Some(_) => Status::Mismatch,
+        None => match ctx.day.filter(|_| !ctx.testing) {
+            Some(day) => store.status(ctx.year, day, nr, answer),
+            None => Status::New,
+        },
+    }
+}
+
+/// The result of a part, or of parsing if `part` is `None`, for `--format json`
+///
+/// Multi-day runs print the same objects, see [`Report`].
+fn result_json(
+    ctx: &RunContext,
+    part: Option<u8>,
+    answer: &Answer,
+    status: Status,
+    parse: Duration,
+    solve: Duration,
+    usage: Option<Usage>,
+) -> Json {
+    let (answer, error) = match answer {
+        Ok(answer) => (Some(answer.as_str()), None),
+        Err(e) => (None, Some(e.as_str())),
+    };
+    Json::object([
+        ("day", ctx.day.into()),
+        ("part", part.into()),
+        ("answer", answer.into()),
+        ("error", error.into()),
+        ("status", status.to_string().into()),
+        ("parse_ns", parse.as_nanos().into()),
+        ("solve_ns", solve.as_nanos().into()),
+        ("allocs", usage.map(|u| u.allocs).into()),
+        ("alloc_bytes", usage.map(|u| u.bytes).into()),
+        ("peak_bytes", usage.map(|u| u.peak).into()),
+        ("input", ctx.input.as_str().into()),
+        ("testing", ctx.testing.into()),
+    ])
+}
+
+/// Where an error happened, for messages
+fn location(ctx: &RunContext, part: Option<u8>) -> String {
+    let phase = match part {
+        Some(nr) => format!("part {nr}"),
+        None => "parsing".to_string(),
+    };
+    match ctx.day {
+        Some(day) => format!("day {day}, {phase}, input {}", ctx.input),
+        None => format!("{phase}, input {}", ctx.input),
+    }
+}
+
+/// Run `f`, with its time and, if counted, its allocations
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration, Option<Usage>) {
+    let ((res, time), usage) = Usage::measure(|| {
+        let start = Instant::now();
+        let res = f();
+        (res, start.elapsed())
+    });
+    (res, time, usage)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn literals() {
+        assert_eq!(literal_answer("3121910778619"), "3121910778619");
+        assert_eq!(literal_answer("1_000u64"), "1000");
+        assert_eq!(literal_answer("- 5"), "-5");
+        assert_eq!(literal_answer("\"ABC\""), "ABC");
+        assert_eq!(literal_answer("'x'"), "x");
+    }
+
+    mod with_params {
+        crate::aoc!(part1; params { steps: u32 = 6 => 64 });
+
+        fn part1(_: String) -> u32 {
+            Params::get().steps
+        }
+
+        #[test]
+        fn params() {
+            let runner = aoc_runner();
+            assert_eq!(runner.answers("")[0], Some(Ok("6".into())));
+            let set = |val: &str| Args {
+                params: vec![("steps".into(), val.into())],
+                ..Args::default()
+            };
+            for (args, testing, steps) in [
+                (Args::default(), true, "6"),
+                (Args::default(), false, "64"),
+                (set("10"), false, "10"),
+                (set("10"), true, "10"),
+            ] {
+                assert!(runner.check_params(&args).is_ok());
+                runner.context(&args, "x", testing).enter();
+                assert_eq!(Solution::part1(&runner, String::new()), Ok(steps.into()));
+            }
+            assert!(runner.check_params(&set("ten")).is_err());
+        }
+    }
+
+    mod sharing {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        crate::aoc!(part1, part2 as clone Vec<bool>);
+
+        static PARSES: AtomicUsize = AtomicUsize::new(0);
+
+        fn input(_: String) -> Vec<bool> {
+            PARSES.fetch_add(1, Ordering::Relaxed);
+            vec![test!()]
+        }
+
+        fn part1(parsed: Vec<bool>) -> bool {
+            parsed[0]
+        }
+
+        fn part2(_: Vec<bool>) -> bool {
+            test!()
+        }
+
+        #[test]
+        fn parsed_once() {
+            let runner = aoc_runner();
+            let yes = Some(Ok("true".to_string()));
+            assert_eq!(runner.answers("x"), [yes.clone(), yes]);
+            assert_eq!(PARSES.load(Ordering::Relaxed), 1);
+            let mut ctx = runner.context(&Args::default(), "inp/1.txt", false);
+            let (.., solved) = runner
+                .solve(&mut ctx, &runner.implemented(), "x".into())
+                .unwrap();
+            let answers: Vec<_> = solved.into_iter().map(|s| s.answer).collect();
+            assert_eq!(answers, [Ok("false".into()), Ok("false".into())]);
+            assert_eq!(PARSES.load(Ordering::Relaxed), 2);
+        }
+    }
+
+    mod alternatives {
+        crate::aoc!(part1 | part1_chars | part1_off, part2 | part2_len);
+
+        fn part1(inp: String) -> usize {
+            inp.len()
+        }
+
+        fn part1_chars(inp: String) -> usize {
+            inp.chars().count()
+        }
+
+        fn part1_off(inp: String) -> usize {
+            inp.len() + 1
+        }
+
+        fn part2(_: String) -> usize {
+            panic!("broken")
+        }
+
+        fn part2_len(inp: String) -> usize {
+            inp.len()
+        }
+
+        #[test]
+        fn cross_check() {
+            let path = std::env::temp_dir().join(format!("aoc-cross-{}.txt", std::process::id()));
+            std::fs::write(&path, "abc\n").unwrap();
+            let args = Args {
+                source: Source::Path(path.to_str().unwrap().into()),
+                ..Args::default()
+            };
+            let runner = aoc_runner();
+            let [part1, part2] = [1, 2].map(|nr| {
+                let mut parts = runner.implemented();
+                parts.retain(|&(n, _)| n == nr);
+                runner.cross_check(&args, &parts)
+            });
+            std::fs::remove_file(path).ok();
+            // the alternatives of a failed part have no answer to disagree with
+            assert_eq!(part1, [Status::Mismatch]);
+            assert_eq!(part2, [Status::Error]);
+            assert_eq!(Status::exit_code(part1), 3);
+            assert_eq!(Status::exit_code(part2), 1);
+        }
+    }
+}
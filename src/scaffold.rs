@@ -0,0 +1,89 @@
+//! Files for a new day
+
+use std::{error::Error, fs, path::Path};
+
+const MAIN: &str = "src/main.rs";
+
+/// Start day `day`: its module, empty input and example files, and
+/// its entry in the [`days!`](crate::days!) list of `src/main.rs`
+///
+/// `data` is the parsed input type, without one the parts take the raw `String`.
+/// The real input goes to `inp/{year}/` if the year is known, the example
+/// to `inp/test-{day}.txt`, next to the `test{n}.txt` of `-t <n>`.
+pub fn new_day(day: u8, data: Option<&str>, year: Option<u16>) -> Result<(), Box<dyn Error>> {
+    let module = format!("src/days/day{day:02}.rs");
+    if Path::new(&module).exists() {
+        return Err(format!("`{module}` already exists").into());
+    }
+    let main = fs::read_to_string(MAIN).map_err(|e| format!("cannot read `{MAIN}`: {e}"))?;
+    let main = register(&main, day).ok_or(format!("no `days!` list in `{MAIN}`"))?;
+    fs::create_dir_all("src/days")?;
+    fs::write(&module, template(day, data))?;
+    fs::write(MAIN, main)?;
+    println!("created {module}");
+    let real = match year {
+        Some(year) => format!("inp/{year}/{day}.txt"),
+        None => format!("inp/{day}.txt"),
+    };
+    for file in [real, format!("inp/test-{day}.txt")] {
+        if !Path::new(&file).exists() {
+            fs::create_dir_all(Path::new(&file).parent().unwrap())?;
+            fs::write(&file, "")?;
+            println!("created {file}");
+        }
+    }
+    Ok(())
+}
+
+fn template(day: u8, data: Option<&str>) -> String {
+    let examples = format!("examples {{\n    \"test-{day}.txt\" => (_, _),\n}}");
+    match data {
+        Some(data) => format!(
+            "aoc::aoc!(part1 as {data}; {examples});\n\n\
+             fn input(_inp: String) -> Data {{\n    todo!()\n}}\n\n\
+             fn part1(_inp: Data) -> u64 {{\n    todo!()\n}}\n"
+        ),
+        None => format!(
+            "aoc::aoc!(part1; {examples});\n\n\
+             fn part1(_inp: String) -> u64 {{\n    todo!()\n}}\n"
+        ),
+    }
+}
+
+/// Add `day` to the `days!` list, keeping it sorted
+fn register(main: &str, day: u8) -> Option<String> {
+    let start = main.find("days! {")? + "days! {".len();
+    let end = start + main[start..].find('}')?;
+    let mut days: Vec<(u8, String)> = main[start..end]
+        .lines()
+        .flat_map(|line| {
+            let (nr, module) = line.split_once("=>")?;
+            let module = module.trim().trim_end_matches(',');
+            Some((nr.trim().parse().ok()?, module.to_string()))
+        })
+        .collect();
+    days.push((day, format!("day{day:02}")));
+    days.sort();
+    let list: String = days
+        .iter()
+        .map(|(nr, module)| format!("    {nr} => {module},\n"))
+        .collect();
+    Some(format!("{}\n{list}{}", &main[..start], &main[end..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn days_list() {
+        let main = "aoc::days! {\n    3 => day03,\n    12 => day12,\n}\n";
+        assert_eq!(
+            register(main, 5).unwrap(),
+            "aoc::days! {\n    3 => day03,\n    5 => day05,\n    12 => day12,\n}\n"
+        );
+        let template = template(5, Some("Map2D<char>"));
+        assert!(template.starts_with("aoc::aoc!(part1 as Map2D<char>; "));
+        assert!(template.contains("\"test-5.txt\" => (_, _)"));
+    }
+}
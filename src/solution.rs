@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use crate::{Answer, PartFn, Runner};
+
+/// A solution that can be driven from code
+///
+/// Days written with [`aoc!`](crate::aoc!) implement it through their
+/// [`Runner`]. Any implementation becomes a `Runner` with
+/// [`Runner::solution`], which adds the command line and example checks.
+pub trait Solution {
+    type Input;
+
+    fn parse(&self, raw: &str) -> Result<Self::Input, String>;
+
+    fn part1(&self, input: Self::Input) -> Answer;
+
+    /// `None` while part 2 is not implemented
+    fn part2(&self, input: Self::Input) -> Option<Answer> {
+        drop(input);
+        None
+    }
+
+    /// The answers of both parts, parsing `raw` for each of them
+    fn solve(&self, raw: &str) -> [Option<Answer>; 2] {
+        let part1 = self.parse(raw).and_then(|input| self.part1(input));
+        let part2 = match self.parse(raw) {
+            Ok(input) => self.part2(input),
+            Err(e) => Some(Err(e)),
+        };
+        [Some(part1), part2]
+    }
+}
+
+impl<D: Send + Sync + 'static> Runner<D> {
+    /// Run a hand-written [`Solution`]
+    pub fn solution<S: Solution<Input = D> + Send + Sync + 'static>(solution: S) -> Self {
+        let solution = Arc::new(solution);
+        let (parse, part1, part2) = (solution.clone(), solution.clone(), solution);
+        Runner::new(move |raw, _| parse.parse(&raw))
+            .part1(PartFn::owned(move |input, _| part1.part1(input)))
+            .part2(PartFn::owned(move |input, _| {
+                part2
+                    .part2(input)
+                    .unwrap_or_else(|| Err("part 2 is not implemented yet".to_string()))
+            }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RunContext;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u32>;
+
+        fn parse(&self, raw: &str) -> Result<Vec<u32>, String> {
+            raw.split_whitespace()
+                .map(|n| n.parse().map_err(|_| format!("not a number: {n}")))
+                .collect()
+        }
+
+        fn part1(&self, input: Vec<u32>) -> Answer {
+            Ok(input.iter().sum::<u32>().to_string())
+        }
+    }
+
+    mod with_params {
+        crate::aoc!(part1, part2; params { steps: u32 = 6 => 64 });
+
+        fn part1(_: String) -> u32 {
+            Params::get().steps
+        }
+
+        fn part2(inp: String) -> usize {
+            inp.len() * Params::get().steps as usize
+        }
+
+        pub(super) fn runner() -> Runner<String> {
+            aoc_runner()
+        }
+    }
+
+    #[test]
+    fn both_ways() {
+        assert_eq!(Sum.solve("1 2 3"), [Some(Ok("6".into())), None]);
+        let runner = Runner::solution(Sum);
+        assert_eq!(runner.answers("4 5")[0], Some(Ok("9".into())));
+        assert_eq!(runner.solve("4 x")[0], Some(Err("not a number: x".into())));
+        let [_, part2] = runner.solve("4 5");
+        assert_eq!(part2, Some(Err("part 2 is not implemented yet".into())));
+        // parameters take their declared values for the current context
+        RunContext::default().enter();
+        let runner = with_params::runner();
+        assert_eq!(
+            runner.solve("ab"),
+            [Some(Ok("64".into())), Some(Ok("128".into()))]
+        );
+        assert_eq!(Solution::part1(&runner, String::new()), Ok("64".into()));
+        RunContext {
+            testing: true,
+            ..Default::default()
+        }
+        .enter();
+        assert_eq!(
+            runner.solve("ab"),
+            [Some(Ok("6".into())), Some(Ok("12".into()))]
+        );
+    }
+}
@@ -56,8 +56,10 @@ impl StrExt for str {
     }
 
     fn paras(&self) -> impl Iterator<Item = &str> {
-        self.split(&format!("{0}{0}", LINE_ENDING))
-            .map(|p| p.strip_suffix(LINE_ENDING).unwrap_or(p))
+        // inputs are normalized, but strings from elsewhere may not be
+        let nl = if self.contains("\r\n") { "\r\n" } else { "\n" };
+        self.split(&format!("{nl}{nl}"))
+            .map(move |p| p.strip_suffix(nl).unwrap_or(p))
             .collect::<Vec<_>>()
             .into_iter()
     }
@@ -137,4 +139,10 @@ mod test {
         let i = s.uints();
         assert_eq!(&i, &[123, 15, 689]);
     }
+
+    #[test]
+    fn paras() {
+        assert_eq!("a\nb\n\nc\n".para_arr(), ["a\nb", "c"]);
+        assert_eq!("a\r\n\r\nc\r\n".para_arr(), ["a", "c"]);
+    }
 }
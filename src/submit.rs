@@ -0,0 +1,308 @@
+use std::{
+    fmt::{self, Display},
+    fs::OpenOptions,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use crate::{AnswerStore, Args, Config, FetchError, Fetcher, fetch::USER_AGENT, http};
+
+/// How the site responded to an answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// answered too recently, seconds left to wait
+    Wait(u64),
+    /// the part is already solved or not unlocked yet
+    WrongLevel,
+}
+
+impl Verdict {
+    /// Read the verdict from the response page
+    pub fn parse(page: &str) -> Option<Self> {
+        Some(if page.contains("That's the right answer") {
+            Verdict::Correct
+        } else if page.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if page.contains("your answer is too low") {
+            Verdict::TooLow
+        } else if page.contains("That's not the right answer") {
+            Verdict::Wrong
+        } else if page.contains("You gave an answer too recently") {
+            let (head, _) = page.split_once("left to wait")?;
+            let wait = head
+                .rsplit("You have")
+                .next()?
+                .split_whitespace()
+                .map(|t| match t.split_at(t.len() - 1) {
+                    (n, "m") => n.parse::<u64>().ok().map(|n| n * 60),
+                    (n, "s") => n.parse().ok(),
+                    _ => None,
+                })
+                .sum::<Option<u64>>()?;
+            Verdict::Wait(wait)
+        } else if page.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            return None;
+        })
+    }
+
+    fn name(self) -> Option<&'static str> {
+        Some(match self {
+            Verdict::Correct => "correct",
+            Verdict::Wrong => "wrong",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wait(_) | Verdict::WrongLevel => None?,
+        })
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            Verdict::Correct,
+            Verdict::Wrong,
+            Verdict::TooHigh,
+            Verdict::TooLow,
+        ]
+        .into_iter()
+        .find(|v| v.name() == Some(name))
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Wait(secs) => write!(f, "answered too recently, wait {secs}s"),
+            Verdict::WrongLevel => write!(f, "already solved or not unlocked"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub verdict: Verdict,
+    pub answer: String,
+}
+
+/// Every answer submitted so far, one tab-separated line per attempt
+pub struct AnswerLog {
+    pub path: PathBuf,
+    pub attempts: Vec<Attempt>,
+}
+
+impl AnswerLog {
+    pub const FILE: &str = "inp/attempts.txt";
+
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let attempts = std::fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .flat_map(|line| {
+                let mut cols = line.splitn(5, '\t');
+                Some(Attempt {
+                    year: cols.next()?.parse().ok()?,
+                    day: cols.next()?.parse().ok()?,
+                    part: cols.next()?.parse().ok()?,
+                    verdict: Verdict::from_name(cols.next()?)?,
+                    answer: cols.next()?.to_string(),
+                })
+            })
+            .collect();
+        Self { path, attempts }
+    }
+
+    /// Reason not to submit `answer`, if there is one
+    pub fn check(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<(), String> {
+        let attempts: Vec<_> = self
+            .attempts
+            .iter()
+            .filter(|a| (a.year, a.day, a.part) == (year, day, part))
+            .collect();
+        if let Some(correct) = attempts.iter().find(|a| a.verdict == Verdict::Correct) {
+            return Err(if correct.answer == answer {
+                "already accepted".to_string()
+            } else {
+                format!("already solved, the answer is {}", correct.answer)
+            });
+        }
+        if let Some(prev) = attempts.iter().find(|a| a.answer == answer) {
+            return Err(format!("already submitted, it was {}", prev.verdict));
+        }
+        let Ok(num) = answer.parse::<i128>() else {
+            return Ok(());
+        };
+        for prev in attempts {
+            let Ok(bound) = prev.answer.parse::<i128>() else {
+                continue;
+            };
+            match prev.verdict {
+                Verdict::TooHigh if num >= bound => {
+                    return Err(format!("{prev} was too high", prev = prev.answer));
+                }
+                Verdict::TooLow if num <= bound => {
+                    return Err(format!("{prev} was too low", prev = prev.answer));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn record(&mut self, attempt: Attempt) -> io::Result<()> {
+        if let Some(name) = attempt.verdict.name() {
+            if let Some(dir) = self.path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            let Attempt {
+                year,
+                day,
+                part,
+                answer,
+                ..
+            } = &attempt;
+            writeln!(file, "{year}\t{day}\t{part}\t{name}\t{answer}")?;
+        }
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+impl Fetcher {
+    /// Post an answer, the verdict is `None` if the response is not understood
+    pub fn submit(
+        &self,
+        year: u16,
+        day: u8,
+        part: u8,
+        answer: &str,
+    ) -> Result<Option<Verdict>, FetchError> {
+        let base_url = self.base_url.as_deref().ok_or(FetchError::NoBaseUrl)?;
+        let session = self.session.as_deref().ok_or(FetchError::NoSession)?;
+        let url = format!("{base_url}/{year}/day/{day}/answer");
+        let cookie = format!("session={session}");
+        let body = format!("level={part}&answer={}", form_encode(answer));
+        let res = http::post(
+            &url,
+            &[("Cookie", &cookie), ("User-Agent", USER_AGENT)],
+            &body,
+        )?;
+        if res.status != 200 {
+            return Err(FetchError::Status(res.status, res.body.trim().to_string()));
+        }
+        Ok(Verdict::parse(&res.body))
+    }
+}
+
+fn form_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Submit the answer of part `nr` for the day selected by `args`,
+/// unless the answer log shows that it cannot be right
+pub fn submit_answer(args: &Args, nr: u8, answer: &str) {
+    let cfg = Config::load();
+    let (Some(year), Some(day)) = (args.event_year(), args.day) else {
+        eprintln!("error: submitting needs a day and a year");
+        return;
+    };
+    let mut log = AnswerLog::load(AnswerLog::FILE);
+    if let Err(reason) = log.check(year, day, nr, answer) {
+        args.note(format!("not submitting part {nr}: {reason}"));
+        return;
+    }
+    match Fetcher::new(&cfg).submit(year, day, nr, answer) {
+        Ok(Some(verdict)) => {
+            args.note(format!("submitted part {nr}: {verdict}"));
+            if verdict == Verdict::Correct
+                && let Err(e) = AnswerStore::load(AnswerStore::FILE).accept(year, day, nr, answer)
+            {
+                eprintln!("error: cannot write {}: {e}", AnswerStore::FILE);
+            }
+            let attempt = Attempt {
+                year,
+                day,
+                part: nr,
+                verdict,
+                answer: answer.to_string(),
+            };
+            if let Err(e) = log.record(attempt) {
+                eprintln!("error: cannot write {}: {e}", AnswerLog::FILE);
+            }
+        }
+        Ok(None) => eprintln!("error: submitted part {nr}, but did not understand the response"),
+        Err(e) => eprintln!("error: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn verdicts() {
+        let page =
+            "<article><p>That's not the right answer; your answer is too high.</p></article>";
+        assert_eq!(Verdict::parse(page), Some(Verdict::TooHigh));
+        let page = "<p>You gave an answer too recently. You have 1m 5s left to wait.</p>";
+        assert_eq!(Verdict::parse(page), Some(Verdict::Wait(65)));
+        assert_eq!(
+            Verdict::parse("<p>That's the right answer!</p>"),
+            Some(Verdict::Correct)
+        );
+        assert_eq!(Verdict::parse("<html></html>"), None);
+    }
+
+    #[test]
+    fn bounds() {
+        let attempt = |verdict, answer: &str| Attempt {
+            year: 2025,
+            day: 3,
+            part: 2,
+            verdict,
+            answer: answer.to_string(),
+        };
+        let mut log = AnswerLog {
+            path: PathBuf::new(),
+            attempts: vec![
+                attempt(Verdict::TooHigh, "500"),
+                attempt(Verdict::TooLow, "100"),
+            ],
+        };
+        assert!(log.check(2025, 3, 2, "300").is_ok());
+        assert!(log.check(2025, 3, 1, "700").is_ok());
+        assert_eq!(
+            log.check(2025, 3, 2, "700"),
+            Err("500 was too high".to_string())
+        );
+        assert_eq!(
+            log.check(2025, 3, 2, "100"),
+            Err("already submitted, it was wrong, too low".to_string())
+        );
+        log.attempts.push(attempt(Verdict::Correct, "300"));
+        assert_eq!(
+            log.check(2025, 3, 2, "301"),
+            Err("already solved, the answer is 300".to_string())
+        );
+    }
+}
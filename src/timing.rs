@@ -0,0 +1,94 @@
+use std::{
+    fmt::{self, Display},
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Summary of repeated time measurements
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    pub fn new(samples: &mut [Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples");
+        samples.sort();
+        let runs = samples.len();
+        let mean = samples.iter().sum::<Duration>() / runs as u32;
+        let var = samples
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / runs as f64;
+        Self {
+            runs,
+            min: samples[0],
+            median: if runs.is_multiple_of(2) {
+                (samples[runs / 2 - 1] + samples[runs / 2]) / 2
+            } else {
+                samples[runs / 2]
+            },
+            mean,
+            max: samples[runs - 1],
+            std_dev: Duration::from_secs_f64(var.sqrt()),
+        }
+    }
+
+    /// Time `run` on `n` inputs created by `setup`
+    ///
+    /// Only `run` is measured.
+    pub fn measure<I, O>(
+        n: usize,
+        mut setup: impl FnMut() -> I,
+        mut run: impl FnMut(I) -> O,
+    ) -> Self {
+        let mut samples: Vec<_> = (0..n.max(1))
+            .map(|_| {
+                let inp = setup();
+                let start = Instant::now();
+                black_box(run(black_box(inp)));
+                start.elapsed()
+            })
+            .collect();
+        Self::new(&mut samples)
+    }
+
+    /// Column titles matching the [`Display`] output
+    pub fn header() -> String {
+        ["min", "median", "mean", "max", "std dev"]
+            .map(|col| format!("{col:>12}"))
+            .concat()
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>12.2?}{:>12.2?}{:>12.2?}{:>12.2?}{:>12.2?}",
+            self.min, self.median, self.mean, self.max, self.std_dev
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let mut samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = Stats::new(&mut samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.max, Duration::from_millis(4));
+        assert_eq!(stats.std_dev.as_micros(), 1118);
+    }
+}
@@ -0,0 +1,96 @@
+//! Polling for changed files, std only
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+    time::SystemTime,
+};
+
+/// Modification times of a set of files, `None` for missing ones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot(Vec<(PathBuf, Option<SystemTime>)>);
+
+impl Snapshot {
+    pub fn of(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self(
+            paths
+                .into_iter()
+                .map(|path| {
+                    let time = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                    (path, time)
+                })
+                .collect(),
+        )
+    }
+
+    /// All `.rs` files below `dir`
+    pub fn sources(dir: impl AsRef<Path>) -> Self {
+        let mut files = Vec::new();
+        let mut dirs = vec![dir.as_ref().to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if path.extension().is_some_and(|e| e == "rs") {
+                    files.push(path);
+                }
+            }
+        }
+        files.sort();
+        Self::of(files)
+    }
+
+    /// Files that differ from `old`
+    pub fn changed<'a>(&'a self, old: &Snapshot) -> Vec<&'a Path> {
+        self.0
+            .iter()
+            .filter(|entry| !old.0.contains(entry))
+            .map(|(path, _)| path.as_path())
+            .collect()
+    }
+}
+
+/// Rebuild with cargo and replace this process with the new binary
+///
+/// Returns only if the build or the restart failed.
+pub fn rebuild() -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let mut build = process::Command::new("cargo");
+    build.args(["build", "-q"]);
+    if exe.components().any(|c| c.as_os_str() == "release") {
+        build.arg("--release");
+    }
+    if !build.status()?.success() {
+        return Err(io::Error::other("build failed"));
+    }
+    let mut restart = process::Command::new(exe);
+    restart.args(std::env::args_os().skip(1));
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        Err(restart.exec())
+    }
+    #[cfg(not(unix))]
+    process::exit(restart.status()?.code().unwrap_or(1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn changes() {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-{}", process::id()));
+        fs::create_dir_all(dir.join("days")).unwrap();
+        fs::write(dir.join("main.rs"), "").unwrap();
+        let before = Snapshot::sources(&dir);
+        assert!(Snapshot::sources(&dir).changed(&before).is_empty());
+        fs::write(dir.join("days/day01.rs"), "").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+        let after = Snapshot::sources(&dir);
+        assert_eq!(after.changed(&before), [dir.join("days/day01.rs")]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}